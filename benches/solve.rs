@@ -0,0 +1,115 @@
+//! Timing run for large instances, where copying the full path into every
+//! discovered state used to dominate. Each instance is timed with the
+//! library's search and, where it finishes in reasonable time, with the
+//! history-map search it replaced. Run with `cargo bench`.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::time::Instant;
+
+use astar::{Problem, SearchProblem, Strategy};
+
+// The two orders timed here: breadth-first, and A* by cost paid plus the
+// heuristic with ties going to the costlier node, as the library orders them.
+// The heap holds indices into the states, which are not ordered themselves.
+enum Queue<S> {
+    Fifo(VecDeque<S>),
+    Heap(BinaryHeap<(Reverse<i64>, i64, usize)>, Vec<Option<S>>),
+}
+
+impl<S> Queue<S> {
+    fn push(&mut self, state: S, cost: i64, estimate: i64) {
+        match self {
+            Queue::Fifo(fifo) => fifo.push_back(state),
+            Queue::Heap(heap, states) => {
+                heap.push((Reverse(cost + estimate), cost, states.len()));
+                states.push(Some(state));
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<S> {
+        match self {
+            Queue::Fifo(fifo) => fifo.pop_front(),
+            Queue::Heap(heap, states) => {
+                let (_, _, index) = heap.pop()?;
+                states[index].take()
+            }
+        }
+    }
+}
+
+// The search before parent pointers: every discovered state keeps its own
+// copy of the whole path to it, and is reopened when a cheaper one is found.
+fn history_search<P: SearchProblem>(problem: &P, strategy: Strategy) -> Option<Vec<P::Action>> {
+    let start = problem.initial_state();
+    let mut history: HashMap<P::State, (Vec<P::Action>, i64)> = HashMap::new();
+    let mut queue = match strategy {
+        Strategy::Bfs => Queue::Fifo(VecDeque::new()),
+        _ => Queue::Heap(BinaryHeap::new(), Vec::new()),
+    };
+
+    history.insert(start.clone(), (Vec::new(), 0));
+    queue.push(start.clone(), 0, problem.heuristic(&start));
+
+    while let Some(state) = queue.pop() {
+        if problem.is_goal(&state) {
+            return history.remove(&state).map(|(path, _)| path);
+        }
+
+        for (next_state, action, step_cost) in problem.successors(&state) {
+            let (path, cost) = &history[&state];
+            let cost = cost + step_cost;
+            if history
+                .get(&next_state)
+                .is_some_and(|(_, known)| *known <= cost)
+            {
+                continue;
+            }
+            let mut next_path = path.clone();
+            next_path.push(action);
+            let estimate = problem.heuristic(&next_state);
+            history.insert(next_state.clone(), (next_path, cost));
+            queue.push(next_state, cost, estimate);
+        }
+    }
+    None
+}
+
+fn main() {
+    for (cannibals, missionaries, boat_capacity, compare) in [
+        (100, 200, 5, true),
+        (300, 600, 5, true),
+        (1000, 2000, 10, false),
+    ] {
+        let problem = Problem::new(cannibals, missionaries, boat_capacity);
+        for strategy in [Strategy::Bfs, Strategy::AStar] {
+            let started = Instant::now();
+            let plan = problem.solve(strategy).unwrap();
+            let elapsed = started.elapsed();
+
+            // The history map did not finish the largest instance in minutes.
+            let baseline = if compare {
+                let started = Instant::now();
+                let path = history_search(&problem, strategy);
+                assert_eq!(
+                    path.map(|path| path.len()),
+                    plan.as_ref().map(|plan| plan.len())
+                );
+                format!("{:?}", started.elapsed())
+            } else {
+                "skipped".to_string()
+            };
+            println!(
+                "{} {}/{}/{}: {:?} trips in {:?} (history map: {})",
+                strategy.name(),
+                cannibals,
+                missionaries,
+                boat_capacity,
+                plan.map(|plan| plan.len()),
+                elapsed,
+                baseline
+            );
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::fmt;
+
+use crate::error::SolveError;
+use crate::plan::Plan;
+use crate::search::{search, SearchProblem, Strategy};
+
+/// The bridge and torch puzzle: people with individual crossing times have to
+/// cross a bridge at night. At most two walk at once, they need the single
+/// torch, and a pair moves at the pace of the slower one. A plan's cost is
+/// its total time.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BridgeAndTorch {
+    pub times: Vec<i64>,
+}
+
+impl BridgeAndTorch {
+    pub fn new(times: Vec<i64>) -> BridgeAndTorch {
+        BridgeAndTorch { times }
+    }
+
+    /// Checks that the crossing times describe a puzzle that can be searched.
+    pub fn validate(&self) -> Result<(), SolveError> {
+        if self.times.iter().any(|time| *time < 0) {
+            return Err(SolveError::NegativeTime);
+        }
+        // No path a search follows visits a state twice, and no walk takes
+        // longer than the slowest person. Each person and the torch can be
+        // on either side.
+        let slowest = self.times.iter().copied().max().unwrap_or(0);
+        u32::try_from(self.times.len() + 1)
+            .ok()
+            .and_then(|sides| 2i64.checked_pow(sides))
+            .and_then(|states| states.checked_mul(slowest))
+            .ok_or(SolveError::Overflow)?;
+        Ok(())
+    }
+
+    /// Searches for a schedule with the given strategy. Only
+    /// [`Strategy::AStar`] and [`Strategy::UniformCost`] are guaranteed to
+    /// find the fastest one.
+    pub fn solve(&self, strategy: Strategy) -> Result<Option<Plan<Walk>>, SolveError> {
+        self.validate()?;
+        Ok(search(self, strategy))
+    }
+
+    fn pace(&self, walk: &Walk) -> i64 {
+        walk.people
+            .iter()
+            .map(|person| self.times[*person])
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Which side every person is on, and where the torch is; `true` is the
+/// starting side.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TorchState {
+    pub people_left: Vec<bool>,
+    pub torch_left: bool,
+}
+
+impl TorchState {
+    /// Whether everybody has crossed. The torch is then on the far side too,
+    /// unless there was nobody to carry it.
+    pub fn is_goal(&self) -> bool {
+        self.people_left.iter().all(|left| !left)
+    }
+}
+
+/// One or two people crossing with the torch, by zero-based index.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Walk {
+    pub people: Vec<usize>,
+    pub move_right: bool,
+}
+
+// Written as `right 1 2`, numbering people from 1, the plain plan format of
+// this puzzle.
+impl fmt::Display for Walk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", if self.move_right { "right" } else { "left" })?;
+        for person in self.people.iter() {
+            write!(f, " {}", person + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl SearchProblem for BridgeAndTorch {
+    type State = TorchState;
+    type Action = Walk;
+
+    fn initial_state(&self) -> TorchState {
+        TorchState {
+            people_left: vec![true; self.times.len()],
+            torch_left: true,
+        }
+    }
+
+    fn successors(&self, state: &TorchState) -> Vec<(TorchState, Walk, i64)> {
+        let with_torch: Vec<usize> = (0..self.times.len())
+            .filter(|person| state.people_left[*person] == state.torch_left)
+            .collect();
+
+        let mut walks = Vec::new();
+        for (index, first) in with_torch.iter().enumerate() {
+            walks.push(vec![*first]);
+            for second in with_torch[index + 1..].iter() {
+                walks.push(vec![*first, *second]);
+            }
+        }
+
+        walks
+            .into_iter()
+            .map(|people| {
+                let mut next_state = state.clone();
+                next_state.torch_left = !state.torch_left;
+                for person in people.iter() {
+                    next_state.people_left[*person] = next_state.torch_left;
+                }
+                let walk = Walk {
+                    people,
+                    move_right: state.torch_left,
+                };
+                let time = self.pace(&walk);
+                (next_state, walk, time)
+            })
+            .collect()
+    }
+
+    fn is_goal(&self, state: &TorchState) -> bool {
+        state.is_goal()
+    }
+
+    // The slowest person still on the starting side has to cross, and if the
+    // torch is on the far side somebody has to bring it back first.
+    fn heuristic(&self, state: &TorchState) -> i64 {
+        let slowest_left = (0..self.times.len())
+            .filter(|person| state.people_left[*person])
+            .map(|person| self.times[person])
+            .max();
+        let Some(slowest_left) = slowest_left else {
+            return 0;
+        };
+        if state.torch_left {
+            return slowest_left;
+        }
+        let fastest_right = (0..self.times.len())
+            .filter(|person| !state.people_left[*person])
+            .map(|person| self.times[person])
+            .min()
+            .unwrap_or(0);
+        fastest_right + slowest_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::replay;
+
+    #[test]
+    fn test_bridge_and_torch_classic() {
+        let problem = BridgeAndTorch::new(vec![1, 2, 5, 10]);
+        let plan = problem.solve(Strategy::AStar).unwrap().unwrap();
+
+        assert_eq!(plan.cost(), 17);
+        assert_eq!(plan.len(), 5);
+        assert!(replay(&problem, plan.moves()).unwrap().is_goal());
+    }
+
+    #[test]
+    fn test_bridge_and_torch_optimum() {
+        // The fastest schedule is known in closed form: the two slowest
+        // either cross together, escorted by the two fastest, or are each
+        // escorted by the fastest.
+        fn fastest(times: &mut Vec<i64>) -> i64 {
+            times.sort_unstable();
+            let mut total = 0;
+            while times.len() > 3 {
+                let [a, b] = [times[0], times[1]];
+                let [y, z] = [times[times.len() - 2], times[times.len() - 1]];
+                total += (a + 2 * b + z).min(2 * a + y + z);
+                times.truncate(times.len() - 2);
+            }
+            total
+                + match times[..] {
+                    [] => 0,
+                    [a] => a,
+                    [_, b] => b,
+                    [a, b, c] => a + b + c,
+                    _ => unreachable!(),
+                }
+        }
+
+        for times in [
+            vec![],
+            vec![7],
+            vec![1, 2, 5, 8],
+            vec![1, 1, 1, 1, 1],
+            vec![3, 1, 4, 1, 5, 9],
+            vec![5, 10, 20, 25],
+        ] {
+            let problem = BridgeAndTorch::new(times.clone());
+            let plan = problem.solve(Strategy::AStar).unwrap().unwrap();
+            assert_eq!(plan.cost(), fastest(&mut times.clone()), "{:?}", times);
+        }
+    }
+
+    #[test]
+    fn test_bridge_and_torch_every_strategy() {
+        let problem = BridgeAndTorch::new(vec![1, 2, 5, 10]);
+        for strategy in Strategy::ALL {
+            let plan = problem.solve(strategy).unwrap().unwrap();
+            assert!(plan.cost() >= 17);
+            assert!(replay(&problem, plan.moves()).unwrap().is_goal());
+        }
+    }
+
+    #[test]
+    fn test_validate_bridge_and_torch() {
+        assert_eq!(BridgeAndTorch::new(vec![1, 2]).validate(), Ok(()));
+        assert_eq!(
+            BridgeAndTorch::new(vec![1, -2]).validate(),
+            Err(SolveError::NegativeTime)
+        );
+        assert_eq!(
+            BridgeAndTorch::new(vec![1, i64::MAX]).validate(),
+            Err(SolveError::Overflow)
+        );
+        // Far from overflowing in 2n walks, but not over every state.
+        assert_eq!(
+            BridgeAndTorch::new(vec![1 << 40; 30]).validate(),
+            Err(SolveError::Overflow)
+        );
+        assert_eq!(
+            BridgeAndTorch::new(vec![1; 70]).validate(),
+            Err(SolveError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use crate::error::SolveError;
+use crate::graph::StateGraph;
+use crate::json::{Json, JsonError};
+use crate::problem::{successors, Problem, Rowers, State};
+use crate::report::{
+    parse_problem, parse_rowers, parse_side, problem_json, rowers_json, state_json,
+};
+
+/// A proof that a problem has no solution: a set of states that holds the
+/// start, not the goal, and that no legal move leaves for long. Every move
+/// from one of its states either stays in the set or leads to a state from
+/// which every move comes straight back, so the goal is never reached.
+///
+/// [`verify_certificate`] checks the proof one state at a time, without
+/// searching.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Certificate {
+    pub problem: Problem,
+    pub states: Vec<State>,
+}
+
+/// Why a [`Certificate`] does not prove its problem unsolvable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CertificateError {
+    /// The problem itself is invalid.
+    Invalid(SolveError),
+    /// A state has counts below zero or above the problem's.
+    OutOfRange(State),
+    /// The start is not in the set.
+    MissingStart,
+    /// The goal is in the set or one move away from it.
+    ReachesGoal,
+    /// A move leads out of the set and a second move does not lead back.
+    Escapes { from: State, to: State },
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateError::Invalid(error) => write!(f, "invalid problem: {}", error),
+            CertificateError::OutOfRange(state) => write!(
+                f,
+                "the state with {}C {}M on the left is outside the problem",
+                state.cannibals_left, state.missionaries_left
+            ),
+            CertificateError::MissingStart => write!(f, "the start is not in the set"),
+            CertificateError::ReachesGoal => write!(f, "the goal can be reached from the set"),
+            CertificateError::Escapes { from, to } => write!(
+                f,
+                "the move from {}C {}M on the left, boat on the {}, to {}C {}M on the \
+                 left, boat on the {}, leaves the set",
+                from.cannibals_left,
+                from.missionaries_left,
+                if from.boat_left { "left" } else { "right" },
+                to.cannibals_left,
+                to.missionaries_left,
+                if to.boat_left { "left" } else { "right" },
+            ),
+        }
+    }
+}
+
+impl Error for CertificateError {}
+
+impl Certificate {
+    /// Collects every state reachable from the start of `problem`, or returns
+    /// `None` when the goal is among them.
+    pub fn prove(problem: &Problem) -> Result<Option<Certificate>, SolveError> {
+        let graph = StateGraph::explore(problem)?;
+        if graph.goal().is_some() {
+            return Ok(None);
+        }
+        Ok(Some(Certificate {
+            problem: *problem,
+            states: graph.states().to_vec(),
+        }))
+    }
+
+    /// Drops every state the proof can do without: a state may go when it is
+    /// not the start and none of its neighbours has gone, since every move
+    /// from it then leads back into the set. On a reachable set this keeps
+    /// roughly the states with the boat on the left, the barrier every
+    /// crossing starts from.
+    pub fn minimize(&self) -> Certificate {
+        let index: HashMap<&State, usize> = self
+            .states
+            .iter()
+            .enumerate()
+            .map(|(index, state)| (state, index))
+            .collect();
+        let mut neighbours = vec![Vec::new(); self.states.len()];
+        for (from, state) in self.states.iter().enumerate() {
+            for (next_state, _) in successors(state, &self.problem) {
+                if let Some(&to) = index.get(&next_state) {
+                    neighbours[from].push(to);
+                    neighbours[to].push(from);
+                }
+            }
+        }
+
+        let start = self.problem.start();
+        let mut kept = vec![true; self.states.len()];
+        for (index, state) in self.states.iter().enumerate() {
+            if *state != start && neighbours[index].iter().all(|&other| kept[other]) {
+                kept[index] = false;
+            }
+        }
+        Certificate {
+            problem: self.problem,
+            states: self
+                .states
+                .iter()
+                .zip(kept)
+                .filter(|(_, kept)| *kept)
+                .map(|(state, _)| state.clone())
+                .collect(),
+        }
+    }
+
+    /// Renders the problem and the states of the set.
+    pub fn to_json(&self) -> String {
+        let states = self
+            .states
+            .iter()
+            .map(|state| self.state_json(state))
+            .collect();
+        Json::object(vec![
+            ("problem", problem_json(&self.problem)),
+            ("states", Json::Array(states)),
+        ])
+        .to_pretty_string()
+    }
+
+    /// Reads a document written by [`Certificate::to_json`], checking that
+    /// the problem is valid and every state lies within its counts. The proof
+    /// itself is left to [`verify_certificate`].
+    pub fn from_json(text: &str) -> Result<Certificate, JsonError> {
+        let document = Json::parse(text)?;
+        let mut certificate = Certificate {
+            problem: parse_problem(document.get("problem")?)?,
+            states: Vec::new(),
+        };
+        certificate
+            .problem
+            .validate()
+            .map_err(|error| JsonError::new(format!("invalid problem: {}", error)))?;
+        for (index, value) in document.get("states")?.as_array()?.iter().enumerate() {
+            let left = value.get("left")?;
+            let cannibals_left = left.get("cannibals")?.as_i64()?;
+            let missionaries_left = left.get("missionaries")?.as_i64()?;
+            let state = State {
+                cannibals_left,
+                missionaries_left,
+                boat_left: parse_side(value.get("boat")?)?,
+                // Without designated rowers everybody on the left can row.
+                rowers_left: match value.get("rowers_left") {
+                    Ok(rowers) => parse_rowers(rowers)?,
+                    Err(_) => Rowers {
+                        cannibals: cannibals_left,
+                        missionaries: missionaries_left,
+                    },
+                },
+            };
+            if !in_range(&certificate.problem, &state) {
+                return Err(JsonError::new(format!(
+                    "state {} has counts outside the problem",
+                    index + 1
+                )));
+            }
+            if value != &certificate.state_json(&state) {
+                return Err(JsonError::new(format!(
+                    "state {} records the wrong bank counts",
+                    index + 1
+                )));
+            }
+            certificate.states.push(state);
+        }
+        Ok(certificate)
+    }
+
+    fn state_json(&self, state: &State) -> Json {
+        let mut json = state_json(&self.problem, state);
+        if let (Json::Object(fields), Some(_)) = (&mut json, self.problem.rowers) {
+            fields.push(("rowers_left".to_string(), rowers_json(&state.rowers_left)));
+        }
+        json
+    }
+}
+
+// Whether every count of `state` lies between none and all of its kind.
+fn in_range(problem: &Problem, state: &State) -> bool {
+    let all_rowers = problem.all_rowers();
+    (0..=problem.cannibals).contains(&state.cannibals_left)
+        && (0..=problem.missionaries).contains(&state.missionaries_left)
+        && (0..=all_rowers.cannibals).contains(&state.rowers_left.cannibals)
+        && (0..=all_rowers.missionaries).contains(&state.rowers_left.missionaries)
+}
+
+/// Checks that `certificate` proves its problem unsolvable: the set holds the
+/// start, and every move from it stays in the set or leads to a state, other
+/// than the goal, whose every move comes back into the set.
+pub fn verify_certificate(certificate: &Certificate) -> Result<(), CertificateError> {
+    let problem = &certificate.problem;
+    problem.validate().map_err(CertificateError::Invalid)?;
+    if let Some(state) = certificate
+        .states
+        .iter()
+        .find(|state| !in_range(problem, state))
+    {
+        return Err(CertificateError::OutOfRange(state.clone()));
+    }
+
+    let states: HashSet<&State> = certificate.states.iter().collect();
+    if !states.contains(&problem.start()) {
+        return Err(CertificateError::MissingStart);
+    }
+    if states.iter().any(|state| state.is_goal()) {
+        return Err(CertificateError::ReachesGoal);
+    }
+    for state in certificate.states.iter() {
+        for (next_state, _) in successors(state, problem) {
+            if states.contains(&next_state) {
+                continue;
+            }
+            if next_state.is_goal() {
+                return Err(CertificateError::ReachesGoal);
+            }
+            for (back, _) in successors(&next_state, problem) {
+                if !states.contains(&back) {
+                    return Err(CertificateError::Escapes {
+                        from: next_state,
+                        to: back,
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prove_unsolvable() {
+        let problem = Problem::new(4, 4, 2);
+        let certificate = Certificate::prove(&problem).unwrap().unwrap();
+        assert_eq!(verify_certificate(&certificate), Ok(()));
+
+        let minimized = certificate.minimize();
+        assert!(minimized.states.len() < certificate.states.len());
+        assert!(minimized.states.iter().all(|state| state.boat_left));
+        assert_eq!(verify_certificate(&minimized), Ok(()));
+
+        assert_eq!(Certificate::prove(&Problem::new(3, 3, 2)), Ok(None));
+        assert_eq!(
+            Certificate::prove(&Problem::new(4, 3, 2)),
+            Err(SolveError::UnsafeInitialState)
+        );
+    }
+
+    #[test]
+    fn test_verify_certificate_rejects() {
+        let problem = Problem::new(4, 4, 2);
+        let certificate = Certificate::prove(&problem).unwrap().unwrap();
+
+        let mut missing = certificate.clone();
+        missing.states.retain(|state| *state != problem.start());
+        assert_eq!(
+            verify_certificate(&missing),
+            Err(CertificateError::MissingStart)
+        );
+
+        let mut open = certificate.minimize();
+        open.states.truncate(1);
+        assert!(matches!(
+            verify_certificate(&open),
+            Err(CertificateError::Escapes { .. })
+        ));
+
+        // The reachable set of a solvable instance leads to its goal.
+        let solvable = Problem::new(3, 3, 2);
+        let states = StateGraph::explore(&solvable).unwrap().states().to_vec();
+        let forged = Certificate {
+            problem: solvable,
+            states: states
+                .into_iter()
+                .filter(|state| !state.is_goal())
+                .collect(),
+        };
+        assert_eq!(
+            verify_certificate(&forged),
+            Err(CertificateError::ReachesGoal)
+        );
+    }
+
+    #[test]
+    fn test_certificate_json_round_trip() {
+        let problem = Problem::new(4, 4, 2).with_rowers(Rowers {
+            cannibals: 1,
+            missionaries: 1,
+        });
+        let certificate = Certificate::prove(&problem).unwrap().unwrap();
+        let text = certificate.to_json();
+        assert_eq!(Certificate::from_json(&text), Ok(certificate.clone()));
+
+        let minimized = certificate.minimize();
+        assert_eq!(Certificate::from_json(&minimized.to_json()), Ok(minimized));
+
+        let certificate = Certificate::prove(&Problem::new(4, 4, 2)).unwrap().unwrap();
+        assert_eq!(
+            Certificate::from_json(&certificate.to_json()),
+            Ok(certificate)
+        );
+
+        // The right bank of the start no longer adds up.
+        let (head, states) = text.split_once("\"states\"").unwrap();
+        let broken = format!(
+            "{}\"states\"{}",
+            head,
+            states.replacen("\"cannibals\": 0", "\"cannibals\": 1", 1)
+        );
+        assert!(Certificate::from_json(&broken).is_err());
+    }
+
+    #[test]
+    fn test_hostile_certificate() {
+        let problem = Problem::new(4, 4, 2).with_rowers(Rowers {
+            cannibals: 1,
+            missionaries: 1,
+        });
+        let text = Certificate::prove(&problem).unwrap().unwrap().to_json();
+        let (head, states) = text.split_once("\"states\"").unwrap();
+        let hostile =
+            |from: &str, to: &str| format!("{}\"states\"{}", head, states.replacen(from, to, 1));
+
+        // The start has 4 cannibals on the left and its single rowing
+        // cannibal with them.
+        for (from, to) in [
+            ("\"cannibals\": 4", "\"cannibals\": -9223372036854775808"),
+            ("\"cannibals\": 0", "\"cannibals\": 9223372036854775807"),
+            ("\"cannibals\": 1", "\"cannibals\": -9223372036854775808"),
+        ] {
+            let error = Certificate::from_json(&hostile(from, to)).unwrap_err();
+            assert!(error.to_string().contains("state 1"), "{}", error);
+        }
+        let invalid = text.replacen("\"boat_capacity\": 2", "\"boat_capacity\": 0", 1);
+        assert!(Certificate::from_json(&invalid).is_err());
+
+        let mut certificate = Certificate::prove(&problem).unwrap().unwrap();
+        certificate.states[1].rowers_left.cannibals = i64::MIN;
+        assert_eq!(
+            verify_certificate(&certificate),
+            Err(CertificateError::OutOfRange(certificate.states[1].clone()))
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::fmt;
+
+use crate::error::SolveError;
+use crate::plan::Plan;
+use crate::problem::estimate_crossings;
+use crate::search::{search, SearchProblem, Strategy};
+
+// Every person is tracked individually and a crossing can take any group
+// from a bank, so the states and boat loads double with each couple; past a
+// dozen couples the search no longer finishes in reasonable time.
+const MAX_COUPLES: i64 = 12;
+
+/// The jealous husbands puzzle: `couples` married couples cross in a boat of
+/// `boat_capacity` seats, and no wife may be on a bank or in the boat with
+/// another man unless her own husband is there too.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct JealousHusbands {
+    pub couples: i64,
+    pub boat_capacity: i64,
+}
+
+impl JealousHusbands {
+    pub fn new(couples: i64, boat_capacity: i64) -> JealousHusbands {
+        JealousHusbands {
+            couples,
+            boat_capacity,
+        }
+    }
+
+    /// Checks that the parameters describe a puzzle that can be searched.
+    pub fn validate(&self) -> Result<(), SolveError> {
+        if self.couples < 0 {
+            return Err(SolveError::NegativeCount);
+        }
+        if self.boat_capacity <= 0 {
+            return Err(SolveError::ZeroCapacity);
+        }
+        if self.couples > MAX_COUPLES {
+            return Err(SolveError::Overflow);
+        }
+        Ok(())
+    }
+
+    /// Searches for a plan with the given strategy. `Ok(None)` means the
+    /// puzzle has no solution.
+    pub fn solve(&self, strategy: Strategy) -> Result<Option<Plan<Passage>>, SolveError> {
+        self.validate()?;
+        Ok(search(self, strategy))
+    }
+}
+
+/// Which bank every person is on; `true` is the left bank. Husband `i` and
+/// wife `i` are married.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CoupleState {
+    pub husbands_left: Vec<bool>,
+    pub wives_left: Vec<bool>,
+    pub boat_left: bool,
+}
+
+impl CoupleState {
+    /// Whether everybody has crossed to the right bank.
+    pub fn is_goal(&self) -> bool {
+        !self.boat_left
+            && self.husbands_left.iter().all(|left| !left)
+            && self.wives_left.iter().all(|left| !left)
+    }
+
+    fn people_left(&self) -> i64 {
+        let husbands = self.husbands_left.iter().filter(|left| **left).count();
+        let wives = self.wives_left.iter().filter(|left| **left).count();
+        (husbands + wives) as i64
+    }
+}
+
+/// The people in the boat for one crossing, by zero-based couple index.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Passage {
+    pub husbands: Vec<usize>,
+    pub wives: Vec<usize>,
+    pub move_right: bool,
+}
+
+// Written as `right h1 w1`, numbering couples from 1, the plain plan format
+// of this puzzle.
+impl fmt::Display for Passage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", if self.move_right { "right" } else { "left" })?;
+        for husband in self.husbands.iter() {
+            write!(f, " h{}", husband + 1)?;
+        }
+        for wife in self.wives.iter() {
+            write!(f, " w{}", wife + 1)?;
+        }
+        Ok(())
+    }
+}
+
+// Whether a group of people is allowed to be together: no wife is with a man
+// unless her husband is among them.
+pub(crate) fn is_proper_company(husbands: &[bool], wives: &[bool]) -> bool {
+    let any_husband = husbands.iter().any(|present| *present);
+    !any_husband
+        || wives
+            .iter()
+            .zip(husbands.iter())
+            .all(|(wife, husband)| !wife || *husband)
+}
+
+// Every way to pick between 1 and `capacity` of `people`, in lexicographic
+// order.
+fn boat_loads(people: &[(bool, usize)], capacity: usize) -> Vec<Vec<(bool, usize)>> {
+    fn extend(
+        people: &[(bool, usize)],
+        capacity: usize,
+        load: &mut Vec<(bool, usize)>,
+        loads: &mut Vec<Vec<(bool, usize)>>,
+    ) {
+        for (index, person) in people.iter().enumerate() {
+            load.push(*person);
+            loads.push(load.clone());
+            if load.len() < capacity {
+                extend(&people[index + 1..], capacity, load, loads);
+            }
+            load.pop();
+        }
+    }
+
+    let mut loads = Vec::new();
+    extend(people, capacity, &mut Vec::new(), &mut loads);
+    loads
+}
+
+impl SearchProblem for JealousHusbands {
+    type State = CoupleState;
+    type Action = Passage;
+
+    fn initial_state(&self) -> CoupleState {
+        let couples = self.couples as usize;
+        CoupleState {
+            husbands_left: vec![true; couples],
+            wives_left: vec![true; couples],
+            boat_left: true,
+        }
+    }
+
+    fn successors(&self, state: &CoupleState) -> Vec<(CoupleState, Passage, i64)> {
+        let couples = state.husbands_left.len();
+        // Husbands first (`false`), then wives (`true`), so that loads come
+        // out in the order `Passage` lists them.
+        let on_boat_side: Vec<(bool, usize)> = [false, true]
+            .into_iter()
+            .flat_map(|is_wife| {
+                let sides = if is_wife {
+                    &state.wives_left
+                } else {
+                    &state.husbands_left
+                };
+                (0..couples)
+                    .filter(move |index| sides[*index] == state.boat_left)
+                    .map(move |index| (is_wife, index))
+            })
+            .collect();
+        let capacity = usize::try_from(self.boat_capacity).unwrap_or(usize::MAX);
+
+        let mut next_states = Vec::new();
+        for load in boat_loads(&on_boat_side, capacity) {
+            let mut husbands_in_boat = vec![false; couples];
+            let mut wives_in_boat = vec![false; couples];
+            let mut next_state = state.clone();
+            next_state.boat_left = !state.boat_left;
+            for (is_wife, index) in load.iter() {
+                if *is_wife {
+                    wives_in_boat[*index] = true;
+                    next_state.wives_left[*index] = next_state.boat_left;
+                } else {
+                    husbands_in_boat[*index] = true;
+                    next_state.husbands_left[*index] = next_state.boat_left;
+                }
+            }
+
+            let bank = |left: bool, sides: &[bool]| -> Vec<bool> {
+                sides.iter().map(|side| *side == left).collect()
+            };
+            let proper = is_proper_company(&husbands_in_boat, &wives_in_boat)
+                && [true, false].into_iter().all(|left| {
+                    is_proper_company(
+                        &bank(left, &next_state.husbands_left),
+                        &bank(left, &next_state.wives_left),
+                    )
+                });
+            if !proper {
+                continue;
+            }
+
+            let passage = Passage {
+                husbands: load
+                    .iter()
+                    .filter(|(is_wife, _)| !is_wife)
+                    .map(|(_, index)| *index)
+                    .collect(),
+                wives: load
+                    .iter()
+                    .filter(|(is_wife, _)| *is_wife)
+                    .map(|(_, index)| *index)
+                    .collect(),
+                move_right: state.boat_left,
+            };
+            next_states.push((next_state, passage, 1));
+        }
+        next_states
+    }
+
+    fn is_goal(&self, state: &CoupleState) -> bool {
+        state.is_goal()
+    }
+
+    // The constraint only removes moves, so the bound of the counting puzzle
+    // still holds.
+    fn heuristic(&self, state: &CoupleState) -> i64 {
+        estimate_crossings(state.people_left(), state.boat_left, self.boat_capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::replay;
+
+    #[test]
+    fn test_is_proper_company() {
+        assert!(is_proper_company(&[true, false], &[true, false]));
+        assert!(is_proper_company(&[false, false], &[true, true]));
+        assert!(is_proper_company(&[true, true], &[true, true]));
+        assert!(!is_proper_company(&[true, false], &[false, true]));
+        assert!(!is_proper_company(&[true, false], &[true, true]));
+    }
+
+    #[test]
+    fn test_boat_loads() {
+        let people = [(false, 0), (false, 1), (true, 0)];
+
+        assert_eq!(boat_loads(&people, 1).len(), 3);
+        assert_eq!(boat_loads(&people, 2).len(), 6);
+        assert_eq!(boat_loads(&people, 5).len(), 7);
+    }
+
+    #[test]
+    fn test_jealous_husbands_known_results() {
+        let cases = [
+            (1, 1, None),
+            (2, 2, Some(5)),
+            (3, 2, Some(11)),
+            (4, 2, None),
+            (4, 3, Some(9)),
+            (5, 3, Some(11)),
+            (6, 3, None),
+            (6, 4, Some(9)),
+        ];
+        for (couples, boat_capacity, trips) in cases {
+            let problem = JealousHusbands::new(couples, boat_capacity);
+            for strategy in [Strategy::Bfs, Strategy::AStar] {
+                let plan = problem.solve(strategy).unwrap();
+                assert_eq!(plan.map(|plan| plan.len()), trips, "{:?}", problem);
+            }
+        }
+    }
+
+    #[test]
+    fn test_jealous_husbands_every_strategy() {
+        let problem = JealousHusbands::new(3, 2);
+        for strategy in Strategy::ALL {
+            let plan = problem.solve(strategy).unwrap().unwrap();
+            assert!(replay(&problem, plan.moves()).unwrap().is_goal());
+        }
+    }
+
+    #[test]
+    fn test_jealous_husbands_rejects_improper_passage() {
+        let problem = JealousHusbands::new(2, 2);
+        let passage = Passage {
+            husbands: vec![0],
+            wives: vec![1],
+            move_right: true,
+        };
+
+        assert!(replay(&problem, std::slice::from_ref(&passage)).is_err());
+        assert_eq!(passage.to_string(), "right h1 w2");
+    }
+
+    #[test]
+    fn test_validate_jealous_husbands() {
+        assert_eq!(JealousHusbands::new(3, 2).validate(), Ok(()));
+        assert_eq!(JealousHusbands::new(0, 1).validate(), Ok(()));
+        assert_eq!(
+            JealousHusbands::new(-1, 2).validate(),
+            Err(SolveError::NegativeCount)
+        );
+        assert_eq!(
+            JealousHusbands::new(3, 0).validate(),
+            Err(SolveError::ZeroCapacity)
+        );
+        assert_eq!(JealousHusbands::new(MAX_COUPLES, 4).validate(), Ok(()));
+        assert_eq!(
+            JealousHusbands::new(100_000_000_000, 4).validate(),
+            Err(SolveError::Overflow)
+        );
+    }
+}
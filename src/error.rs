@@ -0,0 +1,54 @@
+use std::error::Error;
+use std::fmt;
+
+/// Why a [`Problem`](crate::Problem) or another built-in puzzle could not be
+/// searched.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SolveError {
+    /// The number of cannibals or missionaries is negative.
+    NegativeCount,
+    /// The boat cannot carry anybody.
+    ZeroCapacity,
+    /// Cannibals already outnumber the missionaries on the starting bank.
+    UnsafeInitialState,
+    /// More people of a group can row than there are in it.
+    TooManyRowers,
+    /// A crossing time is negative.
+    NegativeTime,
+    /// A trip fee is negative.
+    NegativeCost,
+    /// The river has no locations.
+    NoLocations,
+    /// A boat route joins a location to itself or to one that does not exist.
+    InvalidRoute,
+    /// There is no boat.
+    NoBoats,
+    /// A boat starts at a location that does not exist.
+    InvalidBoat,
+    /// The counts are too large for the search arithmetic.
+    Overflow,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::NegativeCount => write!(f, "counts must not be negative"),
+            SolveError::ZeroCapacity => write!(f, "boat capacity must be at least 1"),
+            SolveError::UnsafeInitialState => {
+                write!(f, "cannibals outnumber missionaries on the starting bank")
+            }
+            SolveError::TooManyRowers => write!(f, "there are more rowers than people"),
+            SolveError::NegativeTime => write!(f, "crossing times must not be negative"),
+            SolveError::NegativeCost => write!(f, "trip fees must not be negative"),
+            SolveError::NoLocations => write!(f, "the river needs at least one location"),
+            SolveError::InvalidRoute => {
+                write!(f, "routes must join two different existing locations")
+            }
+            SolveError::NoBoats => write!(f, "the river needs at least one boat"),
+            SolveError::InvalidBoat => write!(f, "boats must start at an existing location"),
+            SolveError::Overflow => write!(f, "counts are too large to search"),
+        }
+    }
+}
+
+impl Error for SolveError {}
@@ -0,0 +1,207 @@
+use std::fmt;
+
+use crate::search::SearchProblem;
+
+/// Something the farmer has to take across the river.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Item {
+    Wolf,
+    Goat,
+    Cabbage,
+}
+
+impl Item {
+    pub const ALL: [Item; 3] = [Item::Wolf, Item::Goat, Item::Cabbage];
+
+    /// Looks an item up by its name (`wolf`, `goat`, `cabbage`).
+    pub fn from_name(name: &str) -> Option<Item> {
+        Item::ALL.into_iter().find(|item| item.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Item::Wolf => "wolf",
+            Item::Goat => "goat",
+            Item::Cabbage => "cabbage",
+        }
+    }
+}
+
+/// The farmer, wolf, goat and cabbage puzzle. Only the farmer can row and the
+/// boat holds at most one item besides him. Left without the farmer, the wolf
+/// eats the goat and the goat eats the cabbage.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct WolfGoatCabbage;
+
+/// Which bank the farmer and every item are on; `true` is the left bank.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FarmerState {
+    pub farmer_left: bool,
+    pub wolf_left: bool,
+    pub goat_left: bool,
+    pub cabbage_left: bool,
+}
+
+impl FarmerState {
+    /// Whether `item` is on the left bank.
+    pub fn is_left(&self, item: Item) -> bool {
+        match item {
+            Item::Wolf => self.wolf_left,
+            Item::Goat => self.goat_left,
+            Item::Cabbage => self.cabbage_left,
+        }
+    }
+
+    fn set_left(&mut self, item: Item, left: bool) {
+        match item {
+            Item::Wolf => self.wolf_left = left,
+            Item::Goat => self.goat_left = left,
+            Item::Cabbage => self.cabbage_left = left,
+        }
+    }
+
+    /// Whether the farmer and everything he carries are on the right bank.
+    pub fn is_goal(&self) -> bool {
+        !self.farmer_left && Item::ALL.into_iter().all(|item| !self.is_left(item))
+    }
+
+    // Nothing gets eaten on the bank the farmer is not on.
+    fn is_safe(&self) -> bool {
+        let unattended = |item: Item| self.is_left(item) != self.farmer_left;
+        let wolf_eats_goat = unattended(Item::Wolf) && unattended(Item::Goat);
+        let goat_eats_cabbage = unattended(Item::Goat) && unattended(Item::Cabbage);
+        !wolf_eats_goat && !goat_eats_cabbage
+    }
+}
+
+/// The farmer rowing across, alone or with one item.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Crossing {
+    pub passenger: Option<Item>,
+    pub move_right: bool,
+}
+
+// Written as `right goat` or `left alone`, the plain plan format of this
+// puzzle.
+impl fmt::Display for Crossing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            if self.move_right { "right" } else { "left" },
+            self.passenger.map_or("alone", Item::name)
+        )
+    }
+}
+
+impl SearchProblem for WolfGoatCabbage {
+    type State = FarmerState;
+    type Action = Crossing;
+
+    fn initial_state(&self) -> FarmerState {
+        FarmerState {
+            farmer_left: true,
+            wolf_left: true,
+            goat_left: true,
+            cabbage_left: true,
+        }
+    }
+
+    fn successors(&self, state: &FarmerState) -> Vec<(FarmerState, Crossing, i64)> {
+        let passengers = Item::ALL
+            .into_iter()
+            .filter(|item| state.is_left(*item) == state.farmer_left)
+            .map(Some);
+
+        let mut next_states = Vec::new();
+        for passenger in [None].into_iter().chain(passengers) {
+            let mut next_state = *state;
+            next_state.farmer_left = !state.farmer_left;
+            if let Some(item) = passenger {
+                next_state.set_left(item, next_state.farmer_left);
+            }
+            if !next_state.is_safe() {
+                continue;
+            }
+            let crossing = Crossing {
+                passenger,
+                move_right: state.farmer_left,
+            };
+            next_states.push((next_state, crossing, 1));
+        }
+        next_states
+    }
+
+    fn is_goal(&self, state: &FarmerState) -> bool {
+        state.is_goal()
+    }
+
+    // Every item still on the left needs its own trip to the right, and the
+    // farmer has to come back between two of them.
+    fn heuristic(&self, state: &FarmerState) -> i64 {
+        let items_left = Item::ALL
+            .into_iter()
+            .filter(|item| state.is_left(*item))
+            .count() as i64;
+        match (items_left, state.farmer_left) {
+            (0, true) => 1,
+            (0, false) => 0,
+            (_, true) => 2 * items_left - 1,
+            (_, false) => 2 * items_left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::{search, Strategy};
+    use crate::verify::replay;
+
+    #[test]
+    fn test_wolf_goat_cabbage_optimum() {
+        for strategy in [Strategy::Bfs, Strategy::AStar] {
+            let plan = search(&WolfGoatCabbage, strategy).unwrap();
+            assert_eq!(plan.len(), 7);
+            assert_eq!(plan.cost(), 7);
+            assert_eq!(
+                plan.moves()[0],
+                Crossing {
+                    passenger: Some(Item::Goat),
+                    move_right: true,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_wolf_goat_cabbage_every_strategy() {
+        for strategy in Strategy::ALL {
+            let plan = search(&WolfGoatCabbage, strategy).unwrap();
+            assert!(replay(&WolfGoatCabbage, plan.moves()).unwrap().is_goal());
+        }
+    }
+
+    #[test]
+    fn test_wolf_goat_cabbage_unsafe_first_move() {
+        let start = WolfGoatCabbage.initial_state();
+        let passengers: Vec<Option<Item>> = WolfGoatCabbage
+            .successors(&start)
+            .into_iter()
+            .map(|(_, crossing, _)| crossing.passenger)
+            .collect();
+
+        assert_eq!(passengers, vec![Some(Item::Goat)]);
+    }
+
+    #[test]
+    fn test_crossing_display() {
+        let crossing = Crossing {
+            passenger: None,
+            move_right: false,
+        };
+        assert_eq!(crossing.to_string(), "left alone");
+        assert_eq!(Item::from_name("cabbage"), Some(Item::Cabbage));
+        assert_eq!(Item::from_name("farmer"), None);
+    }
+}
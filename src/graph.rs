@@ -0,0 +1,475 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::error::SolveError;
+use crate::json::Json;
+use crate::plan::{Move, Plan};
+use crate::problem::{successors, Problem, State};
+
+/// Every state reachable from the start of a problem under the same rules as
+/// [`Problem::solve`], numbered in the order a breadth-first walk discovers
+/// them, with every legal move between them.
+#[derive(Clone, Debug)]
+pub struct StateGraph {
+    problem: Problem,
+    states: Vec<State>,
+    distances: Vec<usize>,
+    edges: Vec<Edge>,
+}
+
+/// A legal move from one state of a [`StateGraph`] to another, by index.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub movement: Move,
+}
+
+impl StateGraph {
+    /// Walks the state space of `problem` from its start.
+    pub fn explore(problem: &Problem) -> Result<StateGraph, SolveError> {
+        problem.validate()?;
+
+        let start = problem.start();
+        let mut index = HashMap::from([(start.clone(), 0)]);
+        let mut states = vec![start.clone()];
+        let mut distances = vec![0];
+        let mut edges = Vec::new();
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(state) = queue.pop_front() {
+            let from = index[&state];
+            for (next_state, movement) in successors(&state, problem) {
+                let to = *index.entry(next_state.clone()).or_insert_with(|| {
+                    states.push(next_state.clone());
+                    distances.push(distances[from] + 1);
+                    queue.push_back(next_state);
+                    states.len() - 1
+                });
+                edges.push(Edge { from, to, movement });
+            }
+        }
+
+        Ok(StateGraph {
+            problem: *problem,
+            states,
+            distances,
+            edges,
+        })
+    }
+
+    /// The reachable states; the start is at index 0.
+    pub fn states(&self) -> &[State] {
+        &self.states
+    }
+
+    /// Every legal move between reachable states.
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// The index of the goal state, unless it cannot be reached.
+    pub fn goal(&self) -> Option<usize> {
+        self.states.iter().position(State::is_goal)
+    }
+
+    /// The fewest trips from the start to each state, by index.
+    pub fn distances(&self) -> &[usize] {
+        &self.distances
+    }
+
+    /// Whether each state, by index, can still reach the goal.
+    pub fn reaches_goal(&self) -> Vec<bool> {
+        let mut reaches = vec![false; self.states.len()];
+        let Some(goal) = self.goal() else {
+            return reaches;
+        };
+        let mut incoming = vec![Vec::new(); self.states.len()];
+        for edge in self.edges.iter() {
+            incoming[edge.to].push(edge.from);
+        }
+
+        reaches[goal] = true;
+        let mut queue = VecDeque::from([goal]);
+        while let Some(to) = queue.pop_front() {
+            for &from in incoming[to].iter() {
+                if !reaches[from] {
+                    reaches[from] = true;
+                    queue.push_back(from);
+                }
+            }
+        }
+        reaches
+    }
+
+    // The edges a plan follows from the start, as long as it stays legal.
+    fn path_edges(&self, plan: &Plan) -> Vec<usize> {
+        let mut outgoing = vec![Vec::new(); self.states.len()];
+        for (index, edge) in self.edges.iter().enumerate() {
+            outgoing[edge.from].push(index);
+        }
+
+        let mut path = Vec::new();
+        let mut state = 0;
+        for movement in plan.moves() {
+            let Some(&edge) = outgoing[state]
+                .iter()
+                .find(|&&edge| self.edges[edge].movement == *movement)
+            else {
+                break;
+            };
+            path.push(edge);
+            state = self.edges[edge].to;
+        }
+        path
+    }
+
+    /// Renders the graph in Graphviz DOT. Nodes show the bank counts and the
+    /// boat side, edges the move taken. The start and goal are filled, and
+    /// the moves of `plan` with the states they pass are drawn in red. An
+    /// unreachable goal is drawn dashed and unconnected.
+    pub fn to_dot(&self, plan: Option<&Plan>) -> String {
+        let path = plan.map_or(Vec::new(), |plan| self.path_edges(plan));
+        let mut on_path = vec![false; self.states.len()];
+        let mut edge_on_path = vec![false; self.edges.len()];
+        for &edge in path.iter() {
+            on_path[self.edges[edge].from] = true;
+            on_path[self.edges[edge].to] = true;
+            edge_on_path[edge] = true;
+        }
+
+        let mut dot = String::from("digraph states {\n    node [shape=box];\n");
+        for (index, state) in self.states.iter().enumerate() {
+            let mut attributes = vec![format!("label=\"{}\"", self.label(state))];
+            if index == 0 {
+                attributes.push("style=filled, fillcolor=lightblue".to_string());
+            } else if state.is_goal() {
+                attributes.push("style=filled, fillcolor=palegreen".to_string());
+            }
+            if on_path[index] {
+                attributes.push("color=red, penwidth=2".to_string());
+            }
+            dot.push_str(&format!("    s{} [{}];\n", index, attributes.join(", ")));
+        }
+        if self.goal().is_none() {
+            dot.push_str(&format!(
+                "    goal [label=\"{}\", style=dashed];\n",
+                self.label(&self.problem.goal())
+            ));
+        }
+        for (index, edge) in self.edges.iter().enumerate() {
+            let mut attributes = vec![format!("label=\"{}\"", edge.movement)];
+            if edge_on_path[index] {
+                attributes.push("color=red, penwidth=2".to_string());
+            }
+            dot.push_str(&format!(
+                "    s{} -> s{} [{}];\n",
+                edge.from,
+                edge.to,
+                attributes.join(", ")
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the graph in GraphML, with the attributes of
+    /// [`StateGraph::to_json`] as typed data keys. Nodes are named `sN`.
+    pub fn to_graphml(&self) -> String {
+        let nodes = self.node_attributes();
+        let edges = self.edge_attributes();
+
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        );
+        for (target, attributes) in [("node", nodes.first()), ("edge", edges.first())] {
+            for (name, value) in attributes.into_iter().flatten() {
+                let kind = match value {
+                    Json::Bool(_) => "boolean",
+                    Json::Number(_) => "long",
+                    _ => "string",
+                };
+                xml.push_str(&format!(
+                    "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+                    name, target, name, kind
+                ));
+            }
+        }
+        xml.push_str("  <graph id=\"states\" edgedefault=\"directed\">\n");
+        for (index, attributes) in nodes.iter().enumerate() {
+            xml.push_str(&format!("    <node id=\"s{}\">\n", index));
+            push_graphml_data(&mut xml, attributes);
+            xml.push_str("    </node>\n");
+        }
+        for (edge, attributes) in self.edges.iter().zip(edges.iter()) {
+            xml.push_str(&format!(
+                "    <edge source=\"s{}\" target=\"s{}\">\n",
+                edge.from, edge.to
+            ));
+            push_graphml_data(&mut xml, attributes);
+            xml.push_str("    </edge>\n");
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// Renders the graph as a JSON node and edge list. Nodes carry their
+    /// index as `id`, the counts on each bank, the boat side, the distance
+    /// from the start and whether the goal can still be reached; edges carry
+    /// the `source` and `target` indices, the move and its cost.
+    pub fn to_json(&self) -> String {
+        let with_id = |key: &str, id: Json, attributes: Vec<(&'static str, Json)>| {
+            let mut fields = vec![(key.to_string(), id)];
+            fields.extend(
+                attributes
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value)),
+            );
+            Json::Object(fields)
+        };
+        let nodes = self
+            .node_attributes()
+            .into_iter()
+            .enumerate()
+            .map(|(index, attributes)| with_id("id", Json::Number(index as i64), attributes))
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .zip(self.edge_attributes())
+            .map(|(edge, attributes)| {
+                let mut fields = vec![("target", Json::Number(edge.to as i64))];
+                fields.extend(attributes);
+                with_id("source", Json::Number(edge.from as i64), fields)
+            })
+            .collect();
+
+        let mut json = Json::object(vec![
+            ("directed", Json::Bool(true)),
+            ("start", Json::Number(0)),
+            (
+                "goal",
+                self.goal()
+                    .map_or(Json::Null, |goal| Json::Number(goal as i64)),
+            ),
+            ("nodes", Json::Array(nodes)),
+            ("edges", Json::Array(edges)),
+        ])
+        .to_pretty_string();
+        json.push('\n');
+        json
+    }
+
+    fn node_attributes(&self) -> Vec<Vec<(&'static str, Json)>> {
+        let reaches_goal = self.reaches_goal();
+        self.states
+            .iter()
+            .enumerate()
+            .map(|(index, state)| {
+                let mut attributes = vec![
+                    ("cannibals_left", Json::Number(state.cannibals_left)),
+                    ("missionaries_left", Json::Number(state.missionaries_left)),
+                    (
+                        "cannibals_right",
+                        Json::Number(self.problem.cannibals - state.cannibals_left),
+                    ),
+                    (
+                        "missionaries_right",
+                        Json::Number(self.problem.missionaries - state.missionaries_left),
+                    ),
+                    ("boat", side(state.boat_left)),
+                ];
+                if self.problem.rowers.is_some() {
+                    attributes.extend([
+                        (
+                            "rowing_cannibals_left",
+                            Json::Number(state.rowers_left.cannibals),
+                        ),
+                        (
+                            "rowing_missionaries_left",
+                            Json::Number(state.rowers_left.missionaries),
+                        ),
+                    ]);
+                }
+                attributes.extend([
+                    ("distance", Json::Number(self.distances[index] as i64)),
+                    ("reaches_goal", Json::Bool(reaches_goal[index])),
+                ]);
+                attributes
+            })
+            .collect()
+    }
+
+    fn edge_attributes(&self) -> Vec<Vec<(&'static str, Json)>> {
+        self.edges
+            .iter()
+            .map(|edge| {
+                let movement = &edge.movement;
+                let mut attributes = vec![
+                    ("direction", side(!movement.move_right)),
+                    ("cannibals", Json::Number(movement.cannibals_boat)),
+                    ("missionaries", Json::Number(movement.missionaries_boat)),
+                ];
+                if self.problem.rowers.is_some() {
+                    attributes.extend([
+                        ("rowing_cannibals", Json::Number(movement.rowers.cannibals)),
+                        (
+                            "rowing_missionaries",
+                            Json::Number(movement.rowers.missionaries),
+                        ),
+                    ]);
+                }
+                attributes.push(("cost", Json::Number(self.problem.cost.trip_cost(movement))));
+                attributes
+            })
+            .collect()
+    }
+
+    // Bank counts on two lines, with the boat beside its bank, and the people
+    // who can row on the left when not everybody can.
+    fn label(&self, state: &State) -> String {
+        let boat = |left: bool| {
+            if state.boat_left == left {
+                " (boat)"
+            } else {
+                ""
+            }
+        };
+        let mut label = format!(
+            "left: {}C {}M{}\\nright: {}C {}M{}",
+            state.cannibals_left,
+            state.missionaries_left,
+            boat(true),
+            self.problem.cannibals - state.cannibals_left,
+            self.problem.missionaries - state.missionaries_left,
+            boat(false)
+        );
+        if self.problem.rowers.is_some() {
+            label.push_str(&format!(
+                "\\nrowers left: {}C {}M",
+                state.rowers_left.cannibals, state.rowers_left.missionaries
+            ));
+        }
+        label
+    }
+}
+
+fn side(left: bool) -> Json {
+    Json::String(if left { "left" } else { "right" }.to_string())
+}
+
+fn push_graphml_data(xml: &mut String, attributes: &[(&str, Json)]) {
+    for (name, value) in attributes {
+        let text = match value {
+            Json::Bool(value) => value.to_string(),
+            Json::Number(value) => value.to_string(),
+            Json::String(value) => value.clone(),
+            _ => unreachable!("graph attributes are scalars"),
+        };
+        xml.push_str(&format!("      <data key=\"{}\">{}</data>\n", name, text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::Occupancy;
+    use crate::search::Strategy;
+
+    #[test]
+    fn test_explore_classic() {
+        let problem = Problem::new(3, 3, 2);
+        let graph = StateGraph::explore(&problem).unwrap();
+
+        assert_eq!(graph.states()[0], problem.start());
+        assert_eq!(graph.states().len(), 16);
+        assert!(graph.goal().is_some());
+        for edge in graph.edges() {
+            let state = &graph.states()[edge.from];
+            assert!(successors(state, &problem)
+                .iter()
+                .any(|(next, movement)| next == &graph.states()[edge.to]
+                    && movement == &edge.movement));
+        }
+    }
+
+    #[test]
+    fn test_explore_unsolvable() {
+        let graph = StateGraph::explore(&Problem::new(4, 4, 2)).unwrap();
+
+        assert_eq!(graph.goal(), None);
+        assert!(graph.to_dot(None).contains("goal [label="));
+        assert_eq!(
+            StateGraph::explore(&Problem::new(4, 3, 2)).unwrap_err(),
+            SolveError::UnsafeInitialState
+        );
+    }
+
+    #[test]
+    fn test_distances_and_reaches_goal() {
+        let graph = StateGraph::explore(&Problem::new(3, 3, 2)).unwrap();
+        assert_eq!(graph.distances()[graph.goal().unwrap()], 11);
+        assert!(graph.reaches_goal().iter().all(|reaches| *reaches));
+
+        // Once everybody has crossed in a full boat nobody can come back.
+        let problem = Problem::new(1, 1, 2).with_occupancy(Occupancy {
+            min_occupancy: 2,
+            right: None,
+            left: None,
+        });
+        let graph = StateGraph::explore(&problem).unwrap();
+        assert_eq!(graph.states().len(), 2);
+        assert_eq!(graph.distances(), &[0, 1]);
+        assert_eq!(graph.reaches_goal(), vec![true, true]);
+
+        let graph = StateGraph::explore(&Problem::new(4, 4, 2)).unwrap();
+        assert!(graph.reaches_goal().iter().all(|reaches| !*reaches));
+    }
+
+    #[test]
+    fn test_to_json_and_graphml() {
+        let graph = StateGraph::explore(&Problem::new(3, 3, 2)).unwrap();
+
+        let json = Json::parse(&graph.to_json()).unwrap();
+        let nodes = json.get("nodes").unwrap().as_array().unwrap();
+        let edges = json.get("edges").unwrap().as_array().unwrap();
+        assert_eq!(nodes.len(), graph.states().len());
+        assert_eq!(edges.len(), graph.edges().len());
+        let goal = json.get("goal").unwrap().as_i64().unwrap() as usize;
+        assert_eq!(nodes[goal].get("cannibals_right").unwrap().as_i64(), Ok(3));
+        assert_eq!(nodes[goal].get("distance").unwrap().as_i64(), Ok(11));
+        assert_eq!(edges[0].get("source").unwrap().as_i64(), Ok(0));
+        assert_eq!(edges[0].get("direction").unwrap().as_str(), Ok("right"));
+
+        let xml = graph.to_graphml();
+        assert!(xml.contains(
+            "<key id=\"reaches_goal\" for=\"node\" attr.name=\"reaches_goal\" \
+             attr.type=\"boolean\"/>"
+        ));
+        assert!(xml.contains("<key id=\"cost\" for=\"edge\""));
+        assert_eq!(xml.matches("<node id=").count(), graph.states().len());
+        assert_eq!(xml.matches("<edge source=").count(), graph.edges().len());
+        assert!(xml.trim_end().ends_with("</graphml>"));
+
+        let graph = StateGraph::explore(&Problem::new(4, 4, 2)).unwrap();
+        let json = Json::parse(&graph.to_json()).unwrap();
+        assert_eq!(json.get("goal"), Ok(&Json::Null));
+    }
+
+    #[test]
+    fn test_to_dot_highlights_plan() {
+        let problem = Problem::new(1, 1, 2);
+        let graph = StateGraph::explore(&problem).unwrap();
+        let plan = problem.solve(Strategy::Bfs).unwrap().unwrap();
+        let dot = graph.to_dot(Some(&plan));
+
+        assert!(dot.starts_with("digraph states {"));
+        assert!(dot.contains(
+            "s0 [label=\"left: 1C 1M (boat)\\nright: 0C 0M\", \
+             style=filled, fillcolor=lightblue, color=red, penwidth=2];"
+        ));
+        assert!(dot.contains("[label=\"right 1 1\", color=red, penwidth=2];"));
+        assert!(!dot.contains("goal [label="));
+        assert_eq!(dot.matches("->").count(), graph.edges().len());
+    }
+}
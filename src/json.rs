@@ -0,0 +1,431 @@
+use std::error::Error;
+use std::fmt;
+
+/// Why a JSON document could not be read.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JsonError {
+    message: String,
+}
+
+impl JsonError {
+    pub(crate) fn new(message: impl Into<String>) -> JsonError {
+        JsonError {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for JsonError {}
+
+// A minimal JSON value. Numbers are limited to integers, which is all the
+// documents written by this crate contain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub(crate) fn object(fields: Vec<(&str, Json)>) -> Json {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Result<&Json, JsonError> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value)
+                .ok_or_else(|| JsonError::new(format!("missing field `{}`", key))),
+            _ => Err(JsonError::new(format!(
+                "expected an object with field `{}`",
+                key
+            ))),
+        }
+    }
+
+    pub(crate) fn as_i64(&self) -> Result<i64, JsonError> {
+        match self {
+            Json::Number(number) => Ok(*number),
+            _ => Err(JsonError::new("expected an integer")),
+        }
+    }
+
+    pub(crate) fn as_bool(&self) -> Result<bool, JsonError> {
+        match self {
+            Json::Bool(value) => Ok(*value),
+            _ => Err(JsonError::new("expected a boolean")),
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Result<&str, JsonError> {
+        match self {
+            Json::String(value) => Ok(value),
+            _ => Err(JsonError::new("expected a string")),
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Result<&[Json], JsonError> {
+        match self {
+            Json::Array(values) => Ok(values),
+            _ => Err(JsonError::new("expected an array")),
+        }
+    }
+
+    /// Renders the value with two-space indentation.
+    pub(crate) fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            Json::Number(number) => out.push_str(&number.to_string()),
+            Json::String(value) => write_string(out, value),
+            Json::Array(values) if values.is_empty() => out.push_str("[]"),
+            Json::Array(values) => {
+                out.push('[');
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    newline(out, indent + 1);
+                    value.write(out, indent + 1);
+                }
+                newline(out, indent);
+                out.push(']');
+            }
+            Json::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            Json::Object(fields) => {
+                out.push('{');
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    newline(out, indent + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                }
+                newline(out, indent);
+                out.push('}');
+            }
+        }
+    }
+
+    pub(crate) fn parse(text: &str) -> Result<Json, JsonError> {
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            position: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position != parser.chars.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+}
+
+fn newline(out: &mut String, indent: usize) {
+    out.push('\n');
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// Arrays and objects nested deeper than this are rejected rather than
+// parsed by recursing until the stack runs out.
+const MAX_DEPTH: usize = 128;
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> JsonError {
+        JsonError::new(format!("{} at character {}", message, self.position))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.position += 1;
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("expected `{}`", expected)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn keyword(&mut self, word: &str, value: Json) -> Result<Json, JsonError> {
+        for expected in word.chars() {
+            if self.next() != Some(expected) {
+                return Err(self.error(&format!("expected `{}`", word)));
+            }
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json, JsonError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.keyword("null", Json::Null),
+            Some('t') => self.keyword("true", Json::Bool(true)),
+            Some('f') => self.keyword("false", Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => self.nested(Parser::array),
+            Some('{') => self.nested(Parser::object),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Parser) -> Result<Json, JsonError>,
+    ) -> Result<Json, JsonError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("nesting too deep"));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn number(&mut self) -> Result<Json, JsonError> {
+        let start = self.position;
+        if self.peek() == Some('-') {
+            self.position += 1;
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        if self
+            .peek()
+            .is_some_and(|c| c == '.' || c == 'e' || c == 'E')
+        {
+            return Err(self.error("only integers are supported"));
+        }
+        let digits: String = self.chars[start..self.position].iter().collect();
+        digits
+            .parse()
+            .map(Json::Number)
+            .map_err(|_| self.error("invalid integer"))
+    }
+
+    fn hex_escape(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("invalid \\u escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(value),
+                Some('\\') => {
+                    let c = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let mut code = self.hex_escape()?;
+                            if (0xdc00..0xe000).contains(&code) {
+                                return Err(self.error("unpaired surrogate in \\u escape"));
+                            }
+                            if (0xd800..0xdc00).contains(&code) {
+                                self.keyword("\\u", Json::Null)?;
+                                let low = self.hex_escape()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(self.error("unpaired surrogate in \\u escape"));
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            char::from_u32(code).ok_or_else(|| self.error("invalid \\u escape"))?
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    value.push(c);
+                }
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, JsonError> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::Array(values)),
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, JsonError> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::Object(fields)),
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        let value = Json::object(vec![
+            ("null", Json::Null),
+            (
+                "flags",
+                Json::Array(vec![Json::Bool(true), Json::Bool(false)]),
+            ),
+            ("count", Json::Number(-42)),
+            ("text", Json::String("a \"quoted\"\n\tline ☃".to_string())),
+            ("empty", Json::Array(Vec::new())),
+            (
+                "nested",
+                Json::object(vec![("inner", Json::object(vec![]))]),
+            ),
+        ]);
+
+        assert_eq!(Json::parse(&value.to_pretty_string()), Ok(value));
+    }
+
+    #[test]
+    fn test_json_parse() {
+        let value = Json::parse(r#" { "a" : [1, 2 ,3], "b": "é😀\/" } "#).unwrap();
+
+        assert_eq!(
+            value.get("a").unwrap().as_array().unwrap(),
+            &[Json::Number(1), Json::Number(2), Json::Number(3)]
+        );
+        assert_eq!(value.get("b").unwrap().as_str().unwrap(), "é😀/");
+        assert!(value.get("c").is_err());
+    }
+
+    #[test]
+    fn test_json_parse_errors() {
+        for text in [
+            "",
+            "{",
+            "[1,]",
+            "{\"a\" 1}",
+            "tru",
+            "1.5",
+            "\"open",
+            "[1] 2",
+            "{1: 2}",
+            "\"\\ud800\\u0041\"",
+            "\"\\ud800\\ud800\"",
+            "\"\\ud800x\"",
+            "\"\\udc00\"",
+            "{\"problem\":\"\\udfff\\ud800\"}",
+        ] {
+            assert!(Json::parse(text).is_err(), "{}", text);
+        }
+
+        let deep = "[".repeat(200_000);
+        let error = Json::parse(&deep).unwrap_err();
+        assert!(
+            error.to_string().starts_with("nesting too deep"),
+            "{}",
+            error
+        );
+        let nested = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(Json::parse(&nested).is_ok());
+    }
+}
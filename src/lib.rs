@@ -0,0 +1,46 @@
+//! Solver for the missionaries and cannibals river-crossing puzzle, plus the
+//! wolf, goat and cabbage puzzle as [`WolfGoatCabbage`], the jealous
+//! husbands puzzle as [`JealousHusbands`], the bridge and torch puzzle as
+//! [`BridgeAndTorch`] and the river with islands as [`RiverNetwork`].
+//!
+//! Other puzzles can reuse the search strategies by implementing
+//! [`SearchProblem`] and calling [`search`].
+//!
+//! ```
+//! use astar::{Problem, Strategy};
+//!
+//! let plan = Problem::new(3, 3, 2).solve(Strategy::AStar).unwrap().unwrap();
+//! assert_eq!(plan.len(), 11);
+//! ```
+
+mod bridge;
+mod certificate;
+mod couples;
+mod error;
+mod farmer;
+mod graph;
+mod json;
+mod optimal;
+mod plan;
+mod problem;
+mod report;
+mod river;
+mod search;
+mod verify;
+
+pub use bridge::{BridgeAndTorch, TorchState, Walk};
+pub use certificate::{verify_certificate, Certificate, CertificateError};
+pub use couples::{CoupleState, JealousHusbands, Passage};
+pub use error::SolveError;
+pub use farmer::{Crossing, FarmerState, Item, WolfGoatCabbage};
+pub use graph::{Edge, StateGraph};
+pub use json::JsonError;
+pub use optimal::{OptimalPlans, OptimalSolutions, PlanCounts};
+pub use plan::{Move, Plan};
+pub use problem::{
+    is_solvable_analytic, CostModel, Occupancy, Problem, Rowers, SafetyRule, State, TripFee,
+};
+pub use report::{Solution, SOLUTION_SCHEMA};
+pub use river::{Boat, RiverNetwork, RiverState, Trip};
+pub use search::{search, search_counting, Costed, SearchProblem, Strategy};
+pub use verify::{replay, verify_plan, PlanError, Rule};
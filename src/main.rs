@@ -1,311 +1,1924 @@
 use std::cmp;
-use std::collections::{BinaryHeap, HashMap};
-use std::hash::{Hash, Hasher};
-
-#[derive(Clone, Eq, Ord, PartialEq)]
-struct State {
-    cannibals_left: i64,
-    missionaries_left: i64,
-    boat_left: bool,
-}
-
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(
-            score(self.cannibals_left, self.missionaries_left)
-                .cmp(&score(other.cannibals_left, other.missionaries_left))
-                .reverse(),
-        )
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::time::{Duration, Instant};
+
+use astar::{
+    replay, search, search_counting, verify_certificate, verify_plan, Boat, BridgeAndTorch,
+    Certificate, CostModel, CoupleState, Crossing, Item, JealousHusbands, Move, Occupancy, Passage,
+    Plan, Problem, RiverNetwork, RiverState, Rowers, SafetyRule, SearchProblem, Solution,
+    SolveError, StateGraph, Strategy, Trip, TripFee, Walk, WolfGoatCabbage,
+};
+
+fn print_history(history: &[Move]) {
+    for action in history.iter() {
+        println!("===========================================================");
+        if action.move_right {
+            println!(
+                "(→) move right with {} 🧟 and {} 😇",
+                action.cannibals_boat, action.missionaries_boat
+            );
+        } else {
+            println!(
+                "(←) move left with {} 🧟 and {} 😇",
+                action.cannibals_boat, action.missionaries_boat
+            );
+        }
+        if action.rowers.cannibals != action.cannibals_boat
+            || action.rowers.missionaries != action.missionaries_boat
+        {
+            println!(
+                "    rowed by {} 🧟 and {} 😇",
+                action.rowers.cannibals, action.rowers.missionaries
+            );
+        }
+        println!("===========================================================");
+        println!();
     }
 }
 
-impl Hash for State {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.cannibals_left.hash(state);
-        self.missionaries_left.hash(state);
-        self.boat_left.hash(state);
+fn print_result(strategy: &str, result: Option<&Plan>) {
+    match result {
+        Some(plan) => {
+            println!("Found solution! With {}", strategy);
+            println!("===========================================================");
+            println!("🧟 = cannibal");
+            println!("😇 = missionary");
+            println!("===========================================================");
+            println!();
+            println!("step counts: {}", plan.len());
+            println!("total cost: {}", plan.cost());
+            print_history(plan.moves());
+        }
+        None => {
+            println!("===========================================================");
+            println!("No solution found!");
+            println!("===========================================================");
+        }
     }
 }
 
-trait StateQueue {
-    fn push(&mut self, state: State);
-    fn pop(&mut self) -> Option<State>;
-    fn is_empty(&self) -> bool;
+fn print_plain(label: &str, result: Option<&Plan>) {
+    println!("# {}", label);
+    match result {
+        Some(plan) => {
+            println!("# step counts: {}", plan.len());
+            println!("# total cost: {}", plan.cost());
+            plan.moves()
+                .iter()
+                .for_each(|action| println!("{}", action));
+        }
+        None => println!("unsolvable"),
+    }
 }
 
-impl StateQueue for Vec<State> {
-    fn push(&mut self, state: State) {
-        self.push(state);
-    }
-    fn pop(&mut self) -> Option<State> {
-        self.pop()
+fn print_plan(format: OutputFormat, label: &str, solution: &Solution) {
+    match format {
+        OutputFormat::Pretty => print_result(label, solution.plan.as_ref()),
+        OutputFormat::Plain => print_plain(label, solution.plan.as_ref()),
+        OutputFormat::Json => println!("{}", solution.to_json()),
     }
-    fn is_empty(&self) -> bool {
-        self.is_empty()
+}
+
+// Prints a plan of any puzzle: `describe` gives the pretty line of a step and
+// `Display` its plain line. The total cost is printed under `cost_name`.
+fn print_steps<A: fmt::Display>(
+    format: OutputFormat,
+    label: &str,
+    result: Option<&Plan<A>>,
+    cost_name: &str,
+    describe: impl Fn(&A) -> String,
+) {
+    match (format, result) {
+        (OutputFormat::Pretty, Some(plan)) => {
+            println!("Found solution! With {}", label);
+            println!();
+            println!("step counts: {}", plan.len());
+            println!("{}: {}", cost_name, plan.cost());
+            for step in plan.moves() {
+                println!("===========================================================");
+                println!("{}", describe(step));
+                println!("===========================================================");
+                println!();
+            }
+        }
+        (OutputFormat::Pretty, None) => print_result(label, None),
+        (_, result) => {
+            println!("# {}", label);
+            match result {
+                Some(plan) => {
+                    println!("# step counts: {}", plan.len());
+                    println!("# {}: {}", cost_name, plan.cost());
+                    plan.moves().iter().for_each(|step| println!("{}", step));
+                }
+                None => println!("unsolvable"),
+            }
+        }
     }
 }
 
-impl StateQueue for BinaryHeap<State> {
-    fn push(&mut self, state: State) {
-        self.push(state);
+fn describe_crossing(crossing: &Crossing) -> String {
+    format!(
+        "{} farmer crosses {} {}",
+        if crossing.move_right {
+            "(→)"
+        } else {
+            "(←)"
+        },
+        if crossing.move_right { "right" } else { "left" },
+        match crossing.passenger {
+            Some(item) => format!("with the {}", item.name()),
+            None => "alone".to_string(),
+        }
+    )
+}
+
+fn describe_passage(passage: &Passage) -> String {
+    let people: Vec<String> = passage
+        .husbands
+        .iter()
+        .map(|husband| format!("husband {}", husband + 1))
+        .chain(
+            passage
+                .wives
+                .iter()
+                .map(|wife| format!("wife {}", wife + 1)),
+        )
+        .collect();
+    format!(
+        "{} move {} with {}",
+        if passage.move_right { "(→)" } else { "(←)" },
+        if passage.move_right { "right" } else { "left" },
+        people.join(" and ")
+    )
+}
+
+// Instances with more optimal plans than this only report the count.
+const OPTIMAL_LIST_LIMIT: u128 = 100;
+
+fn print_optimal(options: &SolveOptions) -> Result<bool, SolveError> {
+    let Some(solutions) = options.problem.solve_all_optimal()? else {
+        if !options.quiet {
+            let solution = Solution {
+                problem: options.problem,
+                strategy: Strategy::Bfs,
+                plan: None,
+            };
+            print_plan(options.format, "layered predecessor DAG", &solution);
+        }
+        return Ok(false);
+    };
+    if options.quiet {
+        return Ok(true);
+    }
+
+    let comment = match options.format {
+        OutputFormat::Pretty | OutputFormat::Json => "",
+        OutputFormat::Plain => "# ",
+    };
+    let describe = |count: Option<u128>| match count {
+        Some(count) => count.to_string(),
+        None => format!("more than {}", u128::MAX),
+    };
+    let count = solutions.count();
+    println!(
+        "{}optimal plans: {} ({} trips each)",
+        comment,
+        describe(count),
+        solutions.trips()
+    );
+    let counts = options.problem.count_plans(solutions.trips() + 4)?;
+    for trips in (solutions.trips()..=solutions.trips() + 4).step_by(2) {
+        println!(
+            "{}plans with exactly {} trips: {}, with at most {} trips: {}",
+            comment,
+            trips,
+            describe(counts.exactly(trips)),
+            trips,
+            describe(counts.up_to(trips))
+        );
     }
-    fn pop(&mut self) -> Option<State> {
-        self.pop()
+    if count.is_none_or(|count| count > OPTIMAL_LIST_LIMIT) {
+        println!("{}too many to list, showing the count only", comment);
+        return Ok(true);
     }
-    fn is_empty(&self) -> bool {
-        self.is_empty()
+    for (index, plan) in solutions.iter().enumerate() {
+        let solution = Solution {
+            problem: options.problem,
+            strategy: Strategy::Bfs,
+            plan: Some(plan),
+        };
+        print_plan(
+            options.format,
+            &format!("optimal plan #{}", index + 1),
+            &solution,
+        );
     }
+    Ok(true)
+}
+
+const EXIT_SOLVED: i32 = 0;
+const EXIT_UNSOLVABLE: i32 = 1;
+const EXIT_BAD_INPUT: i32 = 2;
+
+const USAGE: &str = "\
+usage: astar <command> [options]
+
+commands:
+  solve    solve one instance
+  verify   check a plan against the rules
+  sweep    solve every instance in a range of parameters and tabulate
+           whether it is solvable, the trips and cost of the plan, the
+           states expanded and the time taken
+  graph    export the reachable state space as Graphviz DOT, with the
+           plan of the strategy highlighted, or as GraphML or JSON with
+           each state's distance from the start and whether it can still
+           reach the goal
+  certify  prove that an instance has no solution, or check a proof
+
+options:
+  -P, --puzzle NAME       missionaries, wolf-goat-cabbage, jealous-husbands,
+                          bridge-and-torch or river (default missionaries)
+  -n, --couples N         number of couples for jealous-husbands, at most 12
+                          (default 3)
+  -t, --times LIST        comma-separated crossing times for bridge-and-torch
+                          (default 1,2,5,10)
+      --routes LIST       boat routes for river as comma-separated FROM-TO
+                          pairs of locations numbered from 0; everybody
+                          starts at 0 and ends at the highest (default
+                          0-1,1-2,0-2, an island between the banks)
+      --boats LIST        boats for river as comma-separated CAPACITY or
+                          CAPACITY@LOCATION (default one boat of -b seats
+                          at 0)
+  -c, --cannibals N       number of cannibals (default 10)
+  -m, --missionaries N    number of missionaries (default 20)
+  -b, --capacity N        boat capacity (default 3, 2 for jealous-husbands)
+      --check PLACES      where cannibals must not outnumber missionaries:
+                          comma-separated left, right, boat (default all three)
+      --surplus K         cannibals allowed beyond the ratio (default 0)
+      --ratio R           cannibals allowed per missionary (default 1)
+  -r, --rowers C,M        only C cannibals and M missionaries can row
+                          (default everybody)
+      --min-occupancy N   fewest people per crossing (default 1)
+      --load-right N      people per crossing to the right: N or an inclusive
+                          range MIN..=MAX (default --min-occupancy..=capacity)
+      --load-left N       the same for crossings to the left
+      --cost-right F,P    each crossing to the right costs F plus P per
+                          person aboard (default 1,0)
+      --cost-left F,P     the same for crossings to the left
+  -s, --strategy NAME     dfs, bfs, greedy, astar, ucs or all (default astar);
+                          astar and ucs find the cheapest plan
+  -f, --format NAME       pretty, plain or json (default pretty); dot,
+                          graphml or json for graph (default dot); plain,
+                          csv or markdown for sweep (default plain)
+  -p, --plan FILE         plan to verify in the plain format, or certificate
+                          to check for certify, - for stdin
+  -q, --quiet             print nothing, only set the exit code
+      --optimal           list every optimal plan and count plans (solve only)
+      --minimize          keep only the states the certificate needs
+                          (certify only)
+      --heatmap           draw the trips of a sweep as an ASCII grid
+                          (sweep with plain output only)
+  -h, --help              show this message
+
+-c, -m, --check, --surplus, --ratio, --rowers, --min-occupancy, --load-right,
+--load-left, --cost-right, --cost-left and --optimal apply to missionaries
+only, and json output is only available for it. --optimal lists the plans with
+the fewest trips, whatever they cost. river takes -c, -m, -b, --check,
+--surplus and --ratio too, with --check left and right meaning the first and
+the last location.
+
+sweep takes N or an inclusive range FROM..=TO for the three counts. The
+trips are the fewest possible with bfs and astar under the default costs.
+
+exit codes: 0 solved, 1 unsolvable, 2 bad input
+verify exits with 0 for a plan that solves the puzzle and 1 otherwise.
+certify prints the reachable states as a JSON certificate and exits with 1
+when there is no solution; with --plan it checks a certificate and exits with
+0 when it holds and 1 otherwise.";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Puzzle {
+    Missionaries,
+    WolfGoatCabbage,
+    JealousHusbands(JealousHusbands),
+    BridgeAndTorch(BridgeAndTorch),
+    River(RiverNetwork),
 }
 
-struct ValidateCannibalMissionaryBalanceProp {
-    cannibals_left: i64,
-    missionaries_left: i64,
-    cannibals_right: i64,
-    missionaries_right: i64,
-    cannibals_boat: i64,
-    missionaries_boat: i64,
+#[derive(Clone, Copy)]
+enum OutputFormat {
+    Pretty,
+    Plain,
+    Json,
 }
 
-fn validate_cannibal_missionary_balance(prop: ValidateCannibalMissionaryBalanceProp) -> bool {
-    let left_side_balance =
-        prop.cannibals_left <= prop.missionaries_left || prop.missionaries_left == 0;
-    let right_side_balance =
-        prop.cannibals_right <= prop.missionaries_right || prop.missionaries_right == 0;
-    let boat_balance = prop.cannibals_boat <= prop.missionaries_boat || prop.missionaries_boat == 0;
+impl OutputFormat {
+    fn parse(name: &str) -> Option<OutputFormat> {
+        match name {
+            "pretty" => Some(OutputFormat::Pretty),
+            "plain" => Some(OutputFormat::Plain),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
 
-    left_side_balance && right_side_balance && boat_balance
+struct SolveOptions {
+    puzzle: Puzzle,
+    problem: Problem,
+    strategies: Vec<Strategy>,
+    format: OutputFormat,
+    quiet: bool,
+    optimal: bool,
 }
 
-#[derive(Clone)]
-struct BoatMovement {
-    cannibals_boat: i64,
-    missionaries_boat: i64,
-    move_right: bool,
+struct SweepOptions {
+    cannibals: (i64, i64),
+    missionaries: (i64, i64),
+    boat_capacity: (i64, i64),
+    safety: SafetyRule,
+    occupancy: Occupancy,
+    cost: CostModel,
+    strategy: Strategy,
+    format: SweepFormat,
+    heatmap: bool,
+    quiet: bool,
 }
 
-fn solve<T: Default + StateQueue>(
-    cannibals_num: i64,
-    missionaries_num: i64,
-    boat_capacity: i64,
-) -> Option<Vec<BoatMovement>> {
-    let state = State {
-        cannibals_left: cannibals_num,
-        missionaries_left: missionaries_num,
-        boat_left: true,
-    };
+#[derive(Clone, Copy)]
+enum SweepFormat {
+    Plain,
+    Csv,
+    Markdown,
+}
 
-    let mut history: HashMap<State, Vec<BoatMovement>> = HashMap::new();
-    let mut queue = T::default();
+#[derive(Clone, Copy)]
+enum GraphFormat {
+    Dot,
+    GraphMl,
+    Json,
+}
 
-    queue.push(state.clone());
+struct GraphOptions {
+    problem: Problem,
+    strategy: Strategy,
+    format: GraphFormat,
+    quiet: bool,
+}
 
-    while !queue.is_empty() {
-        let state = queue.pop().unwrap();
-        let cannibals_left = state.cannibals_left;
-        let missionaries_left = state.missionaries_left;
-        let cannibals_right = cannibals_num - cannibals_left;
-        let missionaries_right = missionaries_num - missionaries_left;
+struct CertifyOptions {
+    problem: Problem,
+    certificate: Option<String>,
+    minimize: bool,
+    quiet: bool,
+}
 
-        if cannibals_left == 0 && missionaries_left == 0 && !state.boat_left {
-            return history.get(&state).map(|value| value.clone());
-        }
+struct VerifyOptions {
+    puzzle: Puzzle,
+    problem: Problem,
+    plan: String,
+    quiet: bool,
+}
 
-        let max_cannibals_on_boat = if state.boat_left {
-            cmp::min(boat_capacity, cannibals_left)
-        } else {
-            cmp::min(boat_capacity, cannibals_right)
+enum Command {
+    Solve(SolveOptions),
+    Verify(VerifyOptions),
+    Sweep(SweepOptions),
+    Graph(GraphOptions),
+    Certify(CertifyOptions),
+    Help,
+}
+
+// Flags given after the subcommand, keyed by their long name.
+struct Flags {
+    values: HashMap<&'static str, String>,
+    switches: Vec<&'static str>,
+}
+
+impl Flags {
+    const VALUED: [(&'static str, &'static str); 20] = [
+        ("-P", "--puzzle"),
+        ("-n", "--couples"),
+        ("-t", "--times"),
+        ("", "--routes"),
+        ("", "--boats"),
+        ("-c", "--cannibals"),
+        ("-m", "--missionaries"),
+        ("-b", "--capacity"),
+        ("-s", "--strategy"),
+        ("-f", "--format"),
+        ("-p", "--plan"),
+        ("", "--check"),
+        ("", "--surplus"),
+        ("", "--ratio"),
+        ("-r", "--rowers"),
+        ("", "--min-occupancy"),
+        ("", "--load-right"),
+        ("", "--load-left"),
+        ("", "--cost-right"),
+        ("", "--cost-left"),
+    ];
+    const SWITCHES: [(&'static str, &'static str); 5] = [
+        ("-q", "--quiet"),
+        ("", "--optimal"),
+        ("", "--minimize"),
+        ("", "--heatmap"),
+        ("-h", "--help"),
+    ];
+
+    fn parse(args: &[String]) -> Result<Flags, String> {
+        let mut flags = Flags {
+            values: HashMap::new(),
+            switches: Vec::new(),
         };
+        let mut args = args.iter();
 
-        let max_missionaries_on_boat = |cannibals_boat: i64| {
-            if state.boat_left {
-                cmp::min(boat_capacity - cannibals_boat, missionaries_left)
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+
+            if let Some(&(_, long)) = Flags::VALUED
+                .iter()
+                .find(|(short, long)| name == *short || name == *long)
+            {
+                let value = match inline_value {
+                    Some(value) => value,
+                    None => args
+                        .next()
+                        .ok_or_else(|| format!("missing value for {}", long))?
+                        .clone(),
+                };
+                flags.values.insert(long, value);
+            } else if let Some(&(_, long)) = Flags::SWITCHES
+                .iter()
+                .find(|(short, long)| name == *short || name == *long)
+            {
+                if inline_value.is_some() {
+                    return Err(format!("{} does not take a value", long));
+                }
+                flags.switches.push(long);
             } else {
-                cmp::min(boat_capacity - cannibals_boat, missionaries_right)
+                return Err(format!("unknown option: {}", arg));
             }
-        };
+        }
+        Ok(flags)
+    }
 
-        for cannibals_boat in 0..=max_cannibals_on_boat {
-            for missionaries_boat in 0..=max_missionaries_on_boat(cannibals_boat) {
-                if cannibals_boat + missionaries_boat == 0 {
-                    continue;
-                }
+    fn has(&self, name: &str) -> bool {
+        self.switches.contains(&name)
+    }
 
-                fn update_counts(left: i64, right: i64, boat: i64, boat_left: bool) -> (i64, i64) {
-                    if boat_left {
-                        (left - boat, right + boat)
-                    } else {
-                        (left + boat, right - boat)
-                    }
-                }
+    fn count(&self, name: &str, default: i64) -> Result<i64, String> {
+        match self.values.get(name) {
+            Some(value) => parse_count(name, value),
+            None => Ok(default),
+        }
+    }
 
-                let (next_cannibals_left, next_cannibals_right) = update_counts(
-                    cannibals_left,
-                    cannibals_right,
-                    cannibals_boat,
-                    state.boat_left,
-                );
-                let (next_missionaries_left, next_missionaries_right) = update_counts(
-                    missionaries_left,
-                    missionaries_right,
-                    missionaries_boat,
-                    state.boat_left,
-                );
-
-                if !validate_cannibal_missionary_balance(ValidateCannibalMissionaryBalanceProp {
-                    cannibals_left: next_cannibals_left,
-                    missionaries_left: next_missionaries_left,
-                    cannibals_right: next_cannibals_right,
-                    missionaries_right: next_missionaries_right,
-                    cannibals_boat,
-                    missionaries_boat,
-                }) {
-                    continue;
+    fn range(&self, name: &str, default: i64) -> Result<(i64, i64), String> {
+        let Some(value) = self.values.get(name) else {
+            return Ok((default, default));
+        };
+        match value.split_once("..=") {
+            Some((from, to)) => {
+                let range = (parse_count(name, from)?, parse_count(name, to)?);
+                if range.0 > range.1 {
+                    return Err(format!("empty range for {}: {}", name, value));
                 }
+                Ok(range)
+            }
+            None => parse_count(name, value).map(|count| (count, count)),
+        }
+    }
+
+    fn strategies(&self) -> Result<Vec<Strategy>, String> {
+        match self.values.get("--strategy").map(String::as_str) {
+            None => Ok(vec![Strategy::AStar]),
+            Some("all") => Ok(Strategy::ALL.to_vec()),
+            Some(name) => Strategy::from_name(name)
+                .map(|strategy| vec![strategy])
+                .ok_or_else(|| {
+                    format!(
+                        "unknown strategy: {} (expected dfs, bfs, greedy, astar, ucs or all)",
+                        name
+                    )
+                }),
+        }
+    }
 
-                let next_state = State {
-                    cannibals_left: next_cannibals_left,
-                    missionaries_left: next_missionaries_left,
-                    boat_left: !state.boat_left,
+    fn puzzle(&self) -> Result<Puzzle, String> {
+        match self.values.get("--puzzle").map(String::as_str) {
+            None | Some("missionaries") => Ok(Puzzle::Missionaries),
+            Some("wolf-goat-cabbage") => Ok(Puzzle::WolfGoatCabbage),
+            Some("jealous-husbands") => Ok(Puzzle::JealousHusbands(JealousHusbands::new(
+                self.count("--couples", 3)?,
+                self.count("--capacity", 2)?,
+            ))),
+            Some("bridge-and-torch") => {
+                let times = self
+                    .values
+                    .get("--times")
+                    .map_or("1,2,5,10", String::as_str)
+                    .split(',')
+                    .map(|time| parse_count("--times", time.trim()))
+                    .collect::<Result<Vec<i64>, String>>()?;
+                Ok(Puzzle::BridgeAndTorch(BridgeAndTorch::new(times)))
+            }
+            Some("river") => {
+                let routes = self
+                    .values
+                    .get("--routes")
+                    .map_or("0-1,1-2,0-2", String::as_str)
+                    .split(',')
+                    .map(|route| parse_route(route.trim()))
+                    .collect::<Result<Vec<(usize, usize)>, String>>()?;
+                let locations = routes
+                    .iter()
+                    .map(|(from, to)| cmp::max(*from, *to) + 1)
+                    .max()
+                    .unwrap_or(1);
+                let network = RiverNetwork::new(
+                    self.count("--cannibals", 10)?,
+                    self.count("--missionaries", 20)?,
+                    self.count("--capacity", 3)?,
+                    locations,
+                    routes,
+                )
+                .with_safety(self.safety()?);
+                let Some(boats) = self.values.get("--boats") else {
+                    return Ok(Puzzle::River(network));
                 };
+                let boats = boats
+                    .split(',')
+                    .map(|boat| parse_boat(boat.trim()))
+                    .collect::<Result<Vec<Boat>, String>>()?;
+                Ok(Puzzle::River(network.with_boats(boats)))
+            }
+            Some(name) => Err(format!(
+                "unknown puzzle: {} (expected missionaries, wolf-goat-cabbage, \
+                 jealous-husbands, bridge-and-torch or river)",
+                name
+            )),
+        }
+    }
 
-                if history.contains_key(&next_state) {
-                    continue;
+    fn safety(&self) -> Result<SafetyRule, String> {
+        let mut safety = SafetyRule {
+            surplus: self.count("--surplus", 0)?,
+            ratio: self.count("--ratio", 1)?,
+            ..SafetyRule::default()
+        };
+        if let Some(places) = self.values.get("--check") {
+            safety.left_bank = false;
+            safety.right_bank = false;
+            safety.boat = false;
+            for place in places.split(',').filter(|place| !place.is_empty()) {
+                match place {
+                    "left" => safety.left_bank = true,
+                    "right" => safety.right_bank = true,
+                    "boat" => safety.boat = true,
+                    _ => {
+                        return Err(format!(
+                            "unknown place for --check: {} (expected left, right or boat)",
+                            place
+                        ))
+                    }
                 }
+            }
+        }
+        Ok(safety)
+    }
 
-                let mut next_history = match history.get(&state) {
-                    Some(value) => value.clone(),
-                    None => Vec::new(),
-                };
-                next_history.push(BoatMovement {
-                    cannibals_boat,
-                    missionaries_boat,
-                    move_right: state.boat_left,
-                });
-                history.insert(next_state.clone(), next_history.clone());
-
-                queue.push(next_state);
+    fn occupancy(&self) -> Result<Occupancy, String> {
+        let limits = |name| match self.values.contains_key(name) {
+            true => self.range(name, 0).map(Some),
+            false => Ok(None),
+        };
+        Ok(Occupancy {
+            min_occupancy: self.count("--min-occupancy", 1)?,
+            right: limits("--load-right")?,
+            left: limits("--load-left")?,
+        })
+    }
+
+    fn cost(&self) -> Result<CostModel, String> {
+        let fee = |name| {
+            let Some(value) = self.values.get(name) else {
+                return Ok(TripFee::default());
+            };
+            let Some((fixed, per_person)) = value.split_once(',') else {
+                return Err(format!("expected F,P for {}, got {}", name, value));
+            };
+            Ok(TripFee {
+                fixed: parse_count(name, fixed)?,
+                per_person: parse_count(name, per_person)?,
+            })
+        };
+        Ok(CostModel {
+            right: fee("--cost-right")?,
+            left: fee("--cost-left")?,
+        })
+    }
+
+    // Applies the rule, occupancy, cost model and rowers given on the command
+    // line to `problem`.
+    fn configure(&self, problem: Problem) -> Result<Problem, String> {
+        let problem = problem
+            .with_safety(self.safety()?)
+            .with_occupancy(self.occupancy()?)
+            .with_cost(self.cost()?);
+        let Some(rowers) = self.values.get("--rowers") else {
+            return Ok(problem);
+        };
+        let Some((cannibals, missionaries)) = rowers.split_once(',') else {
+            return Err(format!("expected C,M for --rowers, got {}", rowers));
+        };
+        Ok(problem.with_rowers(Rowers {
+            cannibals: parse_count("--rowers", cannibals)?,
+            missionaries: parse_count("--rowers", missionaries)?,
+        }))
+    }
+
+    fn format(&self) -> Result<OutputFormat, String> {
+        match self.values.get("--format") {
+            None => Ok(OutputFormat::Pretty),
+            Some(name) => OutputFormat::parse(name).ok_or_else(|| {
+                format!("unknown format: {} (expected pretty, plain or json)", name)
+            }),
+        }
+    }
+}
+
+fn parse_count(name: &str, value: &str) -> Result<i64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number for {}: {}", name, value))
+}
+
+fn parse_route(value: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("expected FROM-TO for --routes, got {}", value);
+    let (from, to) = value.split_once('-').ok_or_else(invalid)?;
+    Ok((
+        from.parse().map_err(|_| invalid())?,
+        to.parse().map_err(|_| invalid())?,
+    ))
+}
+
+fn parse_boat(value: &str) -> Result<Boat, String> {
+    let (capacity, start) = value.split_once('@').unwrap_or((value, "0"));
+    Ok(Boat {
+        capacity: parse_count("--boats", capacity)?,
+        start: start
+            .parse()
+            .map_err(|_| format!("invalid location for --boats: {}", value))?,
+    })
+}
+
+fn parse_command(args: &[String]) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err("missing command".to_string());
+    };
+    if command == "-h" || command == "--help" || command == "help" {
+        return Ok(Command::Help);
+    }
+
+    let flags = Flags::parse(rest)?;
+    if flags.has("--help") {
+        return Ok(Command::Help);
+    }
+
+    match command.as_str() {
+        "solve" => {
+            let options = SolveOptions {
+                puzzle: flags.puzzle()?,
+                problem: flags.configure(Problem::new(
+                    flags.count("--cannibals", 10)?,
+                    flags.count("--missionaries", 20)?,
+                    flags.count("--capacity", 3)?,
+                ))?,
+                strategies: flags.strategies()?,
+                format: flags.format()?,
+                quiet: flags.has("--quiet"),
+                optimal: flags.has("--optimal"),
+            };
+            // A JSON document describes exactly one plan from one strategy.
+            if matches!(options.format, OutputFormat::Json)
+                && (options.optimal || options.strategies.len() != 1)
+            {
+                return Err("json output needs a single strategy and no --optimal".to_string());
             }
+            if options.puzzle != Puzzle::Missionaries
+                && (options.optimal || matches!(options.format, OutputFormat::Json))
+            {
+                return Err("json output and --optimal need the missionaries puzzle".to_string());
+            }
+            Ok(Command::Solve(options))
         }
+        "sweep" => {
+            if flags.puzzle()? != Puzzle::Missionaries {
+                return Err("sweep needs the missionaries puzzle".to_string());
+            }
+            let strategies = flags.strategies()?;
+            if strategies.len() != 1 {
+                return Err("sweep runs a single strategy".to_string());
+            }
+            // The rowers would have to change with the counts.
+            if flags.values.contains_key("--rowers") {
+                return Err("sweep does not take --rowers".to_string());
+            }
+            let format = match flags.values.get("--format").map(String::as_str) {
+                None | Some("plain") => SweepFormat::Plain,
+                Some("csv") => SweepFormat::Csv,
+                Some("markdown") => SweepFormat::Markdown,
+                Some(name) => {
+                    return Err(format!(
+                        "unknown sweep format: {} (expected plain, csv or markdown)",
+                        name
+                    ))
+                }
+            };
+            // A heatmap after the table would break a CSV or Markdown file.
+            if flags.has("--heatmap") && !matches!(format, SweepFormat::Plain) {
+                return Err("--heatmap needs the plain sweep format".to_string());
+            }
+            let options = SweepOptions {
+                cannibals: flags.range("--cannibals", 3)?,
+                missionaries: flags.range("--missionaries", 3)?,
+                boat_capacity: flags.range("--capacity", 2)?,
+                safety: flags.safety()?,
+                occupancy: flags.occupancy()?,
+                cost: flags.cost()?,
+                strategy: strategies[0],
+                format,
+                heatmap: flags.has("--heatmap"),
+                quiet: flags.has("--quiet"),
+            };
+            // Rejected here rather than after half the table has been printed.
+            if options.cannibals.0 < 0 || options.missionaries.0 < 0 {
+                return Err(SolveError::NegativeCount.to_string());
+            }
+            if options.boat_capacity.0 < 1 {
+                return Err(SolveError::ZeroCapacity.to_string());
+            }
+            // The largest instance is the first to overflow. An unsafe start
+            // is only a row of the table.
+            let largest = Problem::new(
+                options.cannibals.1,
+                options.missionaries.1,
+                options.boat_capacity.1,
+            )
+            .with_cost(options.cost);
+            match largest.validate() {
+                Ok(()) | Err(SolveError::UnsafeInitialState) => Ok(Command::Sweep(options)),
+                Err(error) => Err(error.to_string()),
+            }
+        }
+        "verify" => Ok(Command::Verify(VerifyOptions {
+            puzzle: flags.puzzle()?,
+            problem: flags.configure(Problem::new(
+                flags.count("--cannibals", 10)?,
+                flags.count("--missionaries", 20)?,
+                flags.count("--capacity", 3)?,
+            ))?,
+            plan: flags
+                .values
+                .get("--plan")
+                .cloned()
+                .ok_or_else(|| "verify needs --plan FILE".to_string())?,
+            quiet: flags.has("--quiet"),
+        })),
+        "graph" => {
+            if flags.puzzle()? != Puzzle::Missionaries {
+                return Err("graph needs the missionaries puzzle".to_string());
+            }
+            let strategies = flags.strategies()?;
+            if strategies.len() != 1 {
+                return Err("graph highlights the plan of a single strategy".to_string());
+            }
+            let format = match flags.values.get("--format").map(String::as_str) {
+                None | Some("dot") => GraphFormat::Dot,
+                Some("graphml") => GraphFormat::GraphMl,
+                Some("json") => GraphFormat::Json,
+                Some(name) => {
+                    return Err(format!(
+                        "unknown graph format: {} (expected dot, graphml or json)",
+                        name
+                    ))
+                }
+            };
+            Ok(Command::Graph(GraphOptions {
+                problem: flags.configure(Problem::new(
+                    flags.count("--cannibals", 10)?,
+                    flags.count("--missionaries", 20)?,
+                    flags.count("--capacity", 3)?,
+                ))?,
+                strategy: strategies[0],
+                format,
+                quiet: flags.has("--quiet"),
+            }))
+        }
+        "certify" => {
+            if flags.puzzle()? != Puzzle::Missionaries {
+                return Err("certify needs the missionaries puzzle".to_string());
+            }
+            Ok(Command::Certify(CertifyOptions {
+                problem: flags.configure(Problem::new(
+                    flags.count("--cannibals", 10)?,
+                    flags.count("--missionaries", 20)?,
+                    flags.count("--capacity", 3)?,
+                ))?,
+                certificate: flags.values.get("--plan").cloned(),
+                minimize: flags.has("--minimize"),
+                quiet: flags.has("--quiet"),
+            }))
+        }
+        _ => Err(format!("unknown command: {}", command)),
     }
-    return None;
 }
 
-fn score(cannibals_left: i64, missionaries_left: i64) -> i64 {
-    cannibals_left + missionaries_left
+fn describe_walk(problem: &BridgeAndTorch, walk: &Walk) -> String {
+    let people: Vec<String> = walk
+        .people
+        .iter()
+        .map(|person| format!("{} ({} min)", person + 1, problem.times[*person]))
+        .collect();
+    let time = walk
+        .people
+        .iter()
+        .map(|person| problem.times[*person])
+        .max()
+        .unwrap_or(0);
+    format!(
+        "{} {} {} in {} min",
+        if walk.move_right { "(→)" } else { "(←)" },
+        people.join(" and "),
+        if walk.move_right { "cross" } else { "return" },
+        time
+    )
 }
 
-fn print_history(history: &Vec<BoatMovement>) {
-    for action in history.iter() {
-        println!("===========================================================");
-        if action.move_right {
-            println!(
-                "(→) move right with {} 🧟 and {} 😇",
-                action.cannibals_boat, action.missionaries_boat
-            );
+fn describe_trip(problem: &RiverNetwork, trip: &Trip) -> String {
+    let boat = if problem.boats.len() > 1 {
+        format!("boat {} ", trip.boat)
+    } else {
+        String::new()
+    };
+    format!(
+        "({} → {}) {}move with {} 🧟 and {} 😇",
+        trip.from, trip.to, boat, trip.cannibals, trip.missionaries
+    )
+}
+
+fn run_solve(options: &SolveOptions) -> Result<i32, SolveError> {
+    if options.optimal {
+        return Ok(if print_optimal(options)? {
+            EXIT_SOLVED
         } else {
-            println!(
-                "(←) move left with {} 🧟 and {} 😇",
-                action.cannibals_boat, action.missionaries_boat
+            EXIT_UNSOLVABLE
+        });
+    }
+
+    match &options.puzzle {
+        Puzzle::Missionaries => {}
+        Puzzle::WolfGoatCabbage => {
+            return solve_steps(
+                options,
+                |strategy| Ok(search(&WolfGoatCabbage, strategy)),
+                "total cost",
+                describe_crossing,
+            )
+        }
+        Puzzle::JealousHusbands(problem) => {
+            return solve_steps(
+                options,
+                |strategy| problem.solve(strategy),
+                "total cost",
+                describe_passage,
+            )
+        }
+        Puzzle::BridgeAndTorch(problem) => {
+            return solve_steps(
+                options,
+                |strategy| problem.solve(strategy),
+                "total time",
+                |walk| describe_walk(problem, walk),
+            )
+        }
+        Puzzle::River(problem) => {
+            return solve_steps(
+                options,
+                |strategy| problem.solve(strategy),
+                "total cost",
+                |trip| describe_trip(problem, trip),
+            )
+        }
+    }
+
+    let mut solved = true;
+    for strategy in options.strategies.iter() {
+        let solution = Solution {
+            problem: options.problem,
+            strategy: *strategy,
+            plan: options.problem.solve(*strategy)?,
+        };
+        solved &= solution.plan.is_some();
+        if !options.quiet {
+            print_plan(options.format, strategy.label(), &solution);
+        }
+    }
+    Ok(if solved { EXIT_SOLVED } else { EXIT_UNSOLVABLE })
+}
+
+// Solves a puzzle other than missionaries and cannibals with every strategy.
+fn solve_steps<A: fmt::Display>(
+    options: &SolveOptions,
+    solve: impl Fn(Strategy) -> Result<Option<Plan<A>>, SolveError>,
+    cost_name: &str,
+    describe: impl Fn(&A) -> String,
+) -> Result<i32, SolveError> {
+    let mut solved = true;
+    for strategy in options.strategies.iter() {
+        let plan = solve(*strategy)?;
+        solved &= plan.is_some();
+        if !options.quiet {
+            print_steps(
+                options.format,
+                strategy.label(),
+                plan.as_ref(),
+                cost_name,
+                &describe,
             );
         }
-        println!("===========================================================");
+    }
+    Ok(if solved { EXIT_SOLVED } else { EXIT_UNSOLVABLE })
+}
+
+enum SweepOutcome {
+    Unsafe,
+    Unsolvable,
+    Solved(Plan),
+}
+
+// One instance of a sweep with the plan the strategy found, how many states it
+// expanded and how long it took.
+struct SweepRow {
+    cannibals: i64,
+    missionaries: i64,
+    boat_capacity: i64,
+    outcome: SweepOutcome,
+    expanded: usize,
+    time: Duration,
+}
+
+const SWEEP_COLUMNS: [&str; 8] = [
+    "cannibals",
+    "missionaries",
+    "capacity",
+    "solvable",
+    "trips",
+    "cost",
+    "expanded",
+    "time_ms",
+];
+
+impl SweepRow {
+    // The cells under `SWEEP_COLUMNS`, empty where there is no plan.
+    fn cells(&self) -> Vec<String> {
+        let (solvable, trips, cost, expanded) = match &self.outcome {
+            SweepOutcome::Unsafe => ("unsafe", String::new(), String::new(), String::new()),
+            SweepOutcome::Unsolvable => (
+                "no",
+                String::new(),
+                String::new(),
+                self.expanded.to_string(),
+            ),
+            SweepOutcome::Solved(plan) => (
+                "yes",
+                plan.len().to_string(),
+                plan.cost().to_string(),
+                self.expanded.to_string(),
+            ),
+        };
+        vec![
+            self.cannibals.to_string(),
+            self.missionaries.to_string(),
+            self.boat_capacity.to_string(),
+            solvable.to_string(),
+            trips,
+            cost,
+            expanded,
+            format!("{:.3}", self.time.as_secs_f64() * 1000.0),
+        ]
+    }
+}
+
+fn print_sweep_line(format: SweepFormat, cells: &[String]) {
+    match format {
+        SweepFormat::Plain => {
+            let cells: Vec<&str> = cells
+                .iter()
+                .map(|cell| if cell.is_empty() { "-" } else { cell })
+                .collect();
+            println!("{}", cells.join(" "));
+        }
+        SweepFormat::Csv => println!("{}", cells.join(",")),
+        SweepFormat::Markdown => println!("| {} |", cells.join(" | ")),
+    }
+}
+
+// Shades for the heatmap, from the fewest trips in the sweep to the most.
+const SHADES: &[u8] = b".:-=+*#%@";
+
+// Draws the trips of every instance as a grid of cannibals by missionaries,
+// one per boat capacity.
+fn print_heatmap(options: &SweepOptions, rows: &[SweepRow]) {
+    let trips: HashMap<(i64, i64, i64), Option<usize>> = rows
+        .iter()
+        .filter_map(|row| {
+            let trips = match &row.outcome {
+                SweepOutcome::Unsafe => None,
+                SweepOutcome::Unsolvable => Some(None),
+                SweepOutcome::Solved(plan) => Some(Some(plan.len())),
+            }?;
+            Some(((row.cannibals, row.missionaries, row.boat_capacity), trips))
+        })
+        .collect();
+    let fewest = trips.values().flatten().min().copied().unwrap_or(0);
+    let most = trips.values().flatten().max().copied().unwrap_or(0);
+
+    println!();
+    println!(
+        "trips from {} ({}) to {} ({}), blank when unsolvable, x when the start is unsafe",
+        fewest,
+        SHADES[0] as char,
+        most,
+        SHADES[SHADES.len() - 1] as char
+    );
+    for boat_capacity in options.boat_capacity.0..=options.boat_capacity.1 {
         println!();
+        println!(
+            "capacity {}: cannibals down, missionaries across",
+            boat_capacity
+        );
+        let header: String = (options.missionaries.0..=options.missionaries.1)
+            .map(|missionaries| char::from_digit((missionaries % 10) as u32, 10).unwrap())
+            .collect();
+        println!("     {}", header);
+        for cannibals in options.cannibals.0..=options.cannibals.1 {
+            let line: String = (options.missionaries.0..=options.missionaries.1)
+                .map(
+                    |missionaries| match trips.get(&(cannibals, missionaries, boat_capacity)) {
+                        None => 'x',
+                        Some(None) => ' ',
+                        Some(Some(_)) if most == fewest => SHADES[0] as char,
+                        Some(Some(trips)) => {
+                            let shade = (trips - fewest) * (SHADES.len() - 1) / (most - fewest);
+                            SHADES[shade] as char
+                        }
+                    },
+                )
+                .collect();
+            println!("{:>4} {}", cannibals, line);
+        }
     }
 }
 
-fn main() {
-    let cannibals = 10;
-    let missionaries = 20;
-    let boat_capacity = 3;
-
-    let result_vec = solve::<Vec<State>>(cannibals, missionaries, boat_capacity);
-    let result_heap = solve::<BinaryHeap<State>>(cannibals, missionaries, boat_capacity);
-    match result_vec {
-        Some(history) => {
-            println!("Found solution! With Vec<State>");
-            println!("===========================================================");
-            println!("🧟 = cannibal");
-            println!("😇 = missionary");
-            println!("===========================================================");
-            println!();
-            println!("step counts: {}", history.len());
-            print_history(&history);
+fn run_sweep(options: &SweepOptions) -> Result<i32, SolveError> {
+    if !options.quiet {
+        let header = SWEEP_COLUMNS.map(String::from);
+        print_sweep_line(options.format, &header);
+        if let SweepFormat::Markdown = options.format {
+            print_sweep_line(options.format, &SWEEP_COLUMNS.map(|_| "---:".to_string()));
         }
-        None => {
-            println!("===========================================================");
-            println!("No solution found!");
-            println!("===========================================================");
+    }
+    let mut rows = Vec::new();
+    for cannibals in options.cannibals.0..=options.cannibals.1 {
+        for missionaries in options.missionaries.0..=options.missionaries.1 {
+            for boat_capacity in options.boat_capacity.0..=options.boat_capacity.1 {
+                let problem = Problem::new(cannibals, missionaries, boat_capacity)
+                    .with_safety(options.safety)
+                    .with_occupancy(options.occupancy)
+                    .with_cost(options.cost);
+                let started = Instant::now();
+                let (outcome, expanded) = match problem.validate() {
+                    Ok(()) => match search_counting(&problem, options.strategy) {
+                        (Some(plan), expanded) => (SweepOutcome::Solved(plan), expanded),
+                        (None, expanded) => (SweepOutcome::Unsolvable, expanded),
+                    },
+                    Err(SolveError::UnsafeInitialState) => (SweepOutcome::Unsafe, 0),
+                    Err(error) => return Err(error),
+                };
+                let row = SweepRow {
+                    cannibals,
+                    missionaries,
+                    boat_capacity,
+                    outcome,
+                    expanded,
+                    time: started.elapsed(),
+                };
+                if !options.quiet {
+                    print_sweep_line(options.format, &row.cells());
+                }
+                rows.push(row);
+            }
         }
     }
-    match result_heap {
-        Some(history) => {
-            println!("Found solution! With BinaryHeap<State>");
-            println!("===========================================================");
-            println!("🧟 = cannibal");
-            println!("😇 = missionary");
-            println!("===========================================================");
-            println!();
-            println!("step counts: {}", history.len());
-            print_history(&history);
+    if options.heatmap && !options.quiet {
+        print_heatmap(options, &rows);
+    }
+    Ok(EXIT_SOLVED)
+}
+
+// Reads a plan written by `--format plain`, one step per line, with blank
+// lines and `#` comments ignored. `parse` reads the fields of a line and
+// returns `None` when they do not match `expected`.
+fn parse_plain_lines<T>(
+    text: &str,
+    expected: &str,
+    parse: impl Fn(&[&str]) -> Option<T>,
+) -> Result<Vec<T>, String> {
+    let mut steps = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
         }
-        None => {
-            println!("===========================================================");
-            println!("No solution found!");
-            println!("===========================================================");
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let step = parse(&fields).ok_or_else(|| {
+            format!(
+                "line {}: expected `{}`, got `{}`",
+                index + 1,
+                expected,
+                line
+            )
+        })?;
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+// Whether a `right` or `left` field moves to the right.
+fn parse_direction(field: &str) -> Option<bool> {
+    match field {
+        "right" => Some(true),
+        "left" => Some(false),
+        _ => None,
+    }
+}
+
+// Reads moves written by `--format plain`: one `right C M` or `left C M` per
+// line. Two more counts give the rowing cannibals and missionaries when not
+// everybody in the boat rows.
+fn parse_plain_plan(text: &str) -> Result<Vec<Move>, String> {
+    parse_plain_lines(text, "right|left C M [RC RM]", |fields| {
+        let (direction, counts) = fields.split_first()?;
+        if counts.len() != 2 && counts.len() != 4 {
+            return None;
+        }
+        let move_right = parse_direction(direction)?;
+        let counts = counts
+            .iter()
+            .map(|count| count.parse().ok())
+            .collect::<Option<Vec<i64>>>()?;
+        let mut movement = Move::new(counts[0], counts[1], move_right);
+        if let [_, _, cannibals, missionaries] = counts[..] {
+            movement.rowers = Rowers {
+                cannibals,
+                missionaries,
+            };
+        }
+        Some(movement)
+    })
+}
+
+// Reads wolf, goat and cabbage crossings written by `--format plain`: one
+// `right|left ITEM` or `right|left alone` per line.
+fn parse_plain_crossings(text: &str) -> Result<Vec<Crossing>, String> {
+    parse_plain_lines(text, "right|left wolf|goat|cabbage|alone", |fields| {
+        let [direction, passenger] = fields[..] else {
+            return None;
+        };
+        Some(Crossing {
+            passenger: match passenger {
+                "alone" => None,
+                name => Some(Item::from_name(name)?),
+            },
+            move_right: parse_direction(direction)?,
+        })
+    })
+}
+
+// Reads jealous husbands passages written by `--format plain`: a direction
+// followed by `hN` and `wN` for the people in the boat, numbered from 1.
+fn parse_plain_passages(text: &str) -> Result<Vec<Passage>, String> {
+    parse_plain_lines(text, "right|left hN... wN...", |fields| {
+        let (direction, people) = fields.split_first()?;
+        let mut passage = Passage {
+            husbands: Vec::new(),
+            wives: Vec::new(),
+            move_right: parse_direction(direction)?,
+        };
+        for field in people {
+            let (people, number) = match field.split_at_checked(1)? {
+                ("h", number) => (&mut passage.husbands, number),
+                ("w", number) => (&mut passage.wives, number),
+                _ => return None,
+            };
+            let number: usize = number.parse().ok()?;
+            people.push(number.checked_sub(1)?);
+        }
+        // The puzzle lists the people in a boat in increasing order.
+        passage.husbands.sort_unstable();
+        passage.wives.sort_unstable();
+        Some(passage)
+    })
+}
+
+// Reads bridge and torch walks written by `--format plain`: a direction
+// followed by the people crossing, numbered from 1.
+fn parse_plain_walks(text: &str) -> Result<Vec<Walk>, String> {
+    parse_plain_lines(text, "right|left N...", |fields| {
+        let (direction, people) = fields.split_first()?;
+        let move_right = parse_direction(direction)?;
+        let mut people = people
+            .iter()
+            .map(|field| field.parse::<usize>().ok()?.checked_sub(1))
+            .collect::<Option<Vec<usize>>>()?;
+        people.sort_unstable();
+        Some(Walk { people, move_right })
+    })
+}
+
+// Reads river trips written by `--format plain`: `FROM TO C M` per line, with
+// the boat as a fifth field unless it is the first one.
+fn parse_plain_trips(text: &str) -> Result<Vec<Trip>, String> {
+    parse_plain_lines(text, "FROM TO C M [BOAT]", |fields| {
+        let (from, to, cannibals, missionaries, boat) = match fields[..] {
+            [from, to, cannibals, missionaries] => (from, to, cannibals, missionaries, "0"),
+            [from, to, cannibals, missionaries, boat] => (from, to, cannibals, missionaries, boat),
+            _ => return None,
+        };
+        Some(Trip {
+            boat: boat.parse().ok()?,
+            from: from.parse().ok()?,
+            to: to.parse().ok()?,
+            cannibals: cannibals.parse().ok()?,
+            missionaries: missionaries.parse().ok()?,
+        })
+    })
+}
+
+fn read_plan(path: &str) -> Result<String, String> {
+    let mut text = String::new();
+    let result = if path == "-" {
+        io::stdin().read_to_string(&mut text).map(|_| ())
+    } else {
+        fs::read_to_string(path).map(|contents| text = contents)
+    };
+    result.map_err(|error| format!("cannot read {}: {}", path, error))?;
+    Ok(text)
+}
+
+// Checks a plan of a puzzle other than missionaries and cannibals by replaying
+// it; `unfinished` describes where a legal but incomplete plan stops.
+fn verify_steps<P>(
+    options: &VerifyOptions,
+    problem: &P,
+    parse: impl Fn(&str) -> Result<Vec<P::Action>, String>,
+    unfinished: impl Fn(&P::State) -> String,
+) -> i32
+where
+    P: SearchProblem,
+    P::Action: PartialEq,
+{
+    let steps = match read_plan(&options.plan).and_then(|text| parse(&text)) {
+        Ok(steps) => steps,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return EXIT_BAD_INPUT;
+        }
+    };
+
+    let (code, message) = match replay(problem, &steps) {
+        Ok(state) if problem.is_goal(&state) => (
+            EXIT_SOLVED,
+            format!("valid plan: solved in {} trips", steps.len()),
+        ),
+        Ok(state) => (
+            EXIT_UNSOLVABLE,
+            format!("legal but unfinished plan: {}", unfinished(&state)),
+        ),
+        Err(error) => (EXIT_UNSOLVABLE, format!("invalid plan: {}", error)),
+    };
+    if !options.quiet {
+        println!("{}", message);
+    }
+    code
+}
+
+fn side(left: bool) -> &'static str {
+    if left {
+        "left"
+    } else {
+        "right"
+    }
+}
+
+fn run_verify(options: &VerifyOptions) -> Result<i32, SolveError> {
+    match &options.puzzle {
+        Puzzle::Missionaries => {}
+        Puzzle::WolfGoatCabbage => {
+            return Ok(verify_steps(
+                options,
+                &WolfGoatCabbage,
+                parse_plain_crossings,
+                |state| {
+                    let left: Vec<&str> = Item::ALL
+                        .into_iter()
+                        .filter(|item| state.is_left(*item))
+                        .map(Item::name)
+                        .collect();
+                    format!(
+                        "[{}] left, farmer on the {}",
+                        left.join(", "),
+                        side(state.farmer_left)
+                    )
+                },
+            ))
+        }
+        Puzzle::JealousHusbands(problem) => {
+            problem.validate()?;
+            return Ok(verify_steps(
+                options,
+                problem,
+                parse_plain_passages,
+                |state: &CoupleState| {
+                    let people_left = state.husbands_left.iter().filter(|left| **left).count()
+                        + state.wives_left.iter().filter(|left| **left).count();
+                    format!(
+                        "{} people left, boat on the {}",
+                        people_left,
+                        side(state.boat_left)
+                    )
+                },
+            ));
+        }
+        Puzzle::BridgeAndTorch(problem) => {
+            problem.validate()?;
+            return Ok(verify_steps(options, problem, parse_plain_walks, |state| {
+                let people_left = state.people_left.iter().filter(|left| **left).count();
+                format!(
+                    "{} people left, torch on the {}",
+                    people_left,
+                    side(state.torch_left)
+                )
+            }));
+        }
+        Puzzle::River(problem) => {
+            problem.validate()?;
+            return Ok(verify_steps(
+                options,
+                problem,
+                parse_plain_trips,
+                |state: &RiverState| {
+                    let counts: Vec<String> = state
+                        .cannibals
+                        .iter()
+                        .zip(state.missionaries.iter())
+                        .map(|(cannibals, missionaries)| format!("{}/{}", cannibals, missionaries))
+                        .collect();
+                    let boats: Vec<String> =
+                        state.boats.iter().map(|boat| boat.to_string()).collect();
+                    format!(
+                        "cannibals/missionaries by location [{}], boats at [{}]",
+                        counts.join(", "),
+                        boats.join(", ")
+                    )
+                },
+            ));
+        }
+    }
+    options.problem.validate()?;
+
+    let moves = match read_plan(&options.plan).and_then(|text| parse_plain_plan(&text)) {
+        Ok(moves) => moves,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return Ok(EXIT_BAD_INPUT);
+        }
+    };
+
+    let (code, message) = match verify_plan(&options.problem, &moves) {
+        Ok(state) if state.is_goal() => (
+            EXIT_SOLVED,
+            format!("valid plan: solved in {} trips", moves.len()),
+        ),
+        Ok(state) => (
+            EXIT_UNSOLVABLE,
+            format!(
+                "legal but unfinished plan: {} cannibals and {} missionaries left, boat on the {}",
+                state.cannibals_left,
+                state.missionaries_left,
+                side(state.boat_left)
+            ),
+        ),
+        Err(error) => (EXIT_UNSOLVABLE, format!("invalid plan: {}", error)),
+    };
+    if !options.quiet {
+        println!("{}", message);
+    }
+    Ok(code)
+}
+
+fn run_graph(options: &GraphOptions) -> Result<i32, SolveError> {
+    let graph = StateGraph::explore(&options.problem)?;
+    let plan = options.problem.solve(options.strategy)?;
+    if !options.quiet {
+        match options.format {
+            GraphFormat::Dot => print!("{}", graph.to_dot(plan.as_ref())),
+            GraphFormat::GraphMl => print!("{}", graph.to_graphml()),
+            GraphFormat::Json => print!("{}", graph.to_json()),
         }
     }
+    Ok(if plan.is_some() {
+        EXIT_SOLVED
+    } else {
+        EXIT_UNSOLVABLE
+    })
+}
+
+// Prints a certificate that the problem has no solution, or checks the one
+// given with --plan, which brings its own problem.
+fn run_certify(options: &CertifyOptions) -> Result<i32, SolveError> {
+    let Some(path) = &options.certificate else {
+        let Some(certificate) = Certificate::prove(&options.problem)? else {
+            if !options.quiet {
+                println!("solvable: there is no certificate of unsolvability");
+            }
+            return Ok(EXIT_SOLVED);
+        };
+        let certificate = match options.minimize {
+            true => certificate.minimize(),
+            false => certificate,
+        };
+        if !options.quiet {
+            println!("{}", certificate.to_json());
+        }
+        return Ok(EXIT_UNSOLVABLE);
+    };
+
+    let certificate = match read_plan(path)
+        .and_then(|text| Certificate::from_json(&text).map_err(|error| error.to_string()))
+    {
+        Ok(certificate) => certificate,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return Ok(EXIT_BAD_INPUT);
+        }
+    };
+    let (code, message) = match verify_certificate(&certificate) {
+        Ok(()) => (
+            EXIT_SOLVED,
+            format!(
+                "valid certificate: {} states prove the problem unsolvable",
+                certificate.states.len()
+            ),
+        ),
+        Err(error) => (EXIT_UNSOLVABLE, format!("invalid certificate: {}", error)),
+    };
+    if !options.quiet {
+        println!("{}", message);
+    }
+    Ok(code)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match parse_command(&args) {
+        Ok(Command::Solve(options)) => run_solve(&options),
+        Ok(Command::Sweep(options)) => run_sweep(&options),
+        Ok(Command::Verify(options)) => run_verify(&options),
+        Ok(Command::Graph(options)) => run_graph(&options),
+        Ok(Command::Certify(options)) => run_certify(&options),
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            Ok(EXIT_SOLVED)
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!("{}", USAGE);
+            Ok(EXIT_BAD_INPUT)
+        }
+    };
+    let code = result.unwrap_or_else(|error| {
+        eprintln!("error: {}", error);
+        EXIT_BAD_INPUT
+    });
+    std::process::exit(code);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_command_solve() {
+        let Ok(Command::Solve(options)) =
+            parse_command(&args("solve -c 3 --missionaries=4 -b 2 -s bfs -f plain -q"))
+        else {
+            panic!("expected solve command");
+        };
+        assert_eq!(options.problem, Problem::new(3, 4, 2));
+        assert!(matches!(options.strategies[..], [Strategy::Bfs]));
+        assert!(matches!(options.format, OutputFormat::Plain));
+        assert!(options.quiet);
+        assert!(!options.optimal);
+    }
+
+    #[test]
+    fn test_parse_command_safety_rule() {
+        let Ok(Command::Solve(options)) =
+            parse_command(&args("solve --check left,right --surplus 1"))
+        else {
+            panic!("expected solve command");
+        };
+        assert_eq!(
+            options.problem.safety,
+            SafetyRule {
+                boat: false,
+                surplus: 1,
+                ..SafetyRule::default()
+            }
+        );
+
+        let Ok(Command::Solve(options)) = parse_command(&args("solve --check= --ratio 2")) else {
+            panic!("expected solve command");
+        };
+        assert!(!options.problem.safety.left_bank);
+        assert_eq!(options.problem.safety.ratio, 2);
+    }
+
+    #[test]
+    fn test_parse_command_sweep_ranges() {
+        let Ok(Command::Sweep(options)) = parse_command(&args("sweep -c 0..=4 -m 5 -b 2..=3"))
+        else {
+            panic!("expected sweep command");
+        };
+        assert_eq!(options.cannibals, (0, 4));
+        assert_eq!(options.missionaries, (5, 5));
+        assert_eq!(options.boat_capacity, (2, 3));
+        assert!(matches!(options.format, SweepFormat::Plain));
+        assert!(!options.heatmap);
+
+        let Ok(Command::Sweep(options)) = parse_command(&args("sweep -f markdown")) else {
+            panic!("expected sweep command");
+        };
+        assert!(matches!(options.format, SweepFormat::Markdown));
+
+        let Ok(Command::Sweep(options)) = parse_command(&args("sweep --heatmap")) else {
+            panic!("expected sweep command");
+        };
+        assert!(options.heatmap);
+        assert!(parse_command(&args("sweep -f csv --heatmap")).is_err());
+        assert!(parse_command(&args("sweep -f markdown --heatmap")).is_err());
+    }
+
+    #[test]
+    fn test_sweep_row_cells() {
+        let row = SweepRow {
+            cannibals: 4,
+            missionaries: 4,
+            boat_capacity: 2,
+            outcome: SweepOutcome::Unsolvable,
+            expanded: 11,
+            time: Duration::from_micros(1500),
+        };
+        assert_eq!(row.cells(), ["4", "4", "2", "no", "", "", "11", "1.500"]);
+
+        let problem = Problem::new(3, 3, 2);
+        let row = SweepRow {
+            outcome: SweepOutcome::Solved(problem.solve(Strategy::Bfs).unwrap().unwrap()),
+            ..row
+        };
+        assert_eq!(row.cells()[3..6], ["yes", "11", "11"]);
+    }
+
+    #[test]
+    fn test_parse_plain_plan() {
+        let moves = parse_plain_plan("# astar\nright 1 1\n\n  left 0 1\n").unwrap();
+
+        assert_eq!(moves, vec![Move::new(1, 1, true), Move::new(0, 1, false),]);
+        assert!(parse_plain_plan("up 1 1").is_err());
+        assert!(parse_plain_plan("right 1").is_err());
+        assert!(parse_plain_plan("right 1 1 1").is_err());
+        assert!(parse_plain_plan("right one 1").is_err());
+        assert!(parse_plain_plan("unsolvable").is_err());
+    }
+
     #[test]
-    fn test_solve() {
-        let cannibals = 3;
-        let missionaries = 3;
-        let boat_capacity = 2;
+    fn test_parse_plain_crossings() {
+        let crossings = parse_plain_crossings("# bfs\nright goat\nleft alone\n").unwrap();
 
-        let result_vec = solve::<Vec<State>>(cannibals, missionaries, boat_capacity);
-        let result_heap = solve::<BinaryHeap<State>>(cannibals, missionaries, boat_capacity);
+        assert_eq!(
+            crossings,
+            vec![
+                Crossing {
+                    passenger: Some(Item::Goat),
+                    move_right: true,
+                },
+                Crossing {
+                    passenger: None,
+                    move_right: false,
+                },
+            ]
+        );
+        assert!(parse_plain_crossings("right farmer").is_err());
+        assert!(parse_plain_crossings("up goat").is_err());
+        assert!(parse_plain_crossings("right goat wolf").is_err());
+    }
 
-        assert!(result_vec.is_some());
-        assert!(result_heap.is_some());
+    #[test]
+    fn test_parse_command_jealous_husbands() {
+        let Ok(Command::Solve(options)) =
+            parse_command(&args("solve -P jealous-husbands -n 5 -b 3"))
+        else {
+            panic!("expected solve command");
+        };
+        assert_eq!(
+            options.puzzle,
+            Puzzle::JealousHusbands(JealousHusbands::new(5, 3))
+        );
     }
 
     #[test]
-    fn test_solve_no_solution() {
-        let cannibals = 4;
-        let missionaries = 3;
-        let boat_capacity = 2;
+    fn test_parse_plain_passages() {
+        let passages = parse_plain_passages("right w2 h1\nleft w1\n").unwrap();
 
-        let result_vec = solve::<Vec<State>>(cannibals, missionaries, boat_capacity);
-        let result_heap = solve::<BinaryHeap<State>>(cannibals, missionaries, boat_capacity);
+        assert_eq!(
+            passages,
+            vec![
+                Passage {
+                    husbands: vec![0],
+                    wives: vec![1],
+                    move_right: true,
+                },
+                Passage {
+                    husbands: Vec::new(),
+                    wives: vec![0],
+                    move_right: false,
+                },
+            ]
+        );
+        assert!(parse_plain_passages("right h0").is_err());
+        assert!(parse_plain_passages("right x1").is_err());
+        assert!(parse_plain_passages("up w1").is_err());
+    }
+
+    #[test]
+    fn test_parse_command_bridge_and_torch() {
+        let Ok(Command::Solve(options)) =
+            parse_command(&args("solve -P bridge-and-torch -t 1,3,4"))
+        else {
+            panic!("expected solve command");
+        };
+        assert_eq!(
+            options.puzzle,
+            Puzzle::BridgeAndTorch(BridgeAndTorch::new(vec![1, 3, 4]))
+        );
+        assert!(parse_command(&args("solve -P bridge-and-torch -t 1,,2")).is_err());
+    }
 
-        assert!(result_vec.is_none());
-        assert!(result_heap.is_none());
+    #[test]
+    fn test_parse_command_river() {
+        let Ok(Command::Solve(options)) =
+            parse_command(&args("solve -P river -c 4 -m 4 -b 2 --routes 0-1,1-3"))
+        else {
+            panic!("expected solve command");
+        };
+        assert_eq!(
+            options.puzzle,
+            Puzzle::River(RiverNetwork::new(4, 4, 2, 4, vec![(0, 1), (1, 3)]))
+        );
+
+        let Ok(Command::Solve(options)) = parse_command(&args("solve -P river -c 4 -m 4 -b 2"))
+        else {
+            panic!("expected solve command");
+        };
+        assert_eq!(options.puzzle, Puzzle::River(RiverNetwork::island(4, 4, 2)));
+        assert!(parse_command(&args("solve -P river --routes 0:1")).is_err());
+        assert!(parse_command(&args("solve -P river --routes 0-x")).is_err());
+
+        let Ok(Command::Solve(options)) =
+            parse_command(&args("solve -P river -c 1 -m 1 --boats 1,2@2"))
+        else {
+            panic!("expected solve command");
+        };
+        let Puzzle::River(network) = options.puzzle else {
+            panic!("expected river puzzle");
+        };
+        assert_eq!(
+            network.boats,
+            vec![
+                Boat {
+                    capacity: 1,
+                    start: 0,
+                },
+                Boat {
+                    capacity: 2,
+                    start: 2,
+                },
+            ]
+        );
+        assert!(parse_command(&args("solve -P river --boats 2@far")).is_err());
+    }
+
+    #[test]
+    fn test_parse_plain_trips() {
+        let trips = parse_plain_trips("# bfs\n0 1 1 1\n1 0 0 1 2\n").unwrap();
+
+        assert_eq!(trips[0].boat, 0);
+        assert_eq!(
+            trips[1],
+            Trip {
+                boat: 2,
+                from: 1,
+                to: 0,
+                cannibals: 0,
+                missionaries: 1,
+            }
+        );
+        assert!(parse_plain_trips("0 1 1").is_err());
+        assert!(parse_plain_trips("0 1 1 one").is_err());
+        assert!(parse_plain_trips("0 1 1 1 0 0").is_err());
+    }
+
+    #[test]
+    fn test_parse_plain_walks() {
+        let walks = parse_plain_walks("# total time: 3\nright 2 1\nleft 1\n").unwrap();
+
+        assert_eq!(
+            walks,
+            vec![
+                Walk {
+                    people: vec![0, 1],
+                    move_right: true,
+                },
+                Walk {
+                    people: vec![0],
+                    move_right: false,
+                },
+            ]
+        );
+        assert!(parse_plain_walks("right 0").is_err());
+        assert!(parse_plain_walks("across 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_plain_plan_rowers() {
+        let moves = parse_plain_plan("right 1 2 0 1").unwrap();
+
+        assert_eq!(
+            moves[0].rowers,
+            Rowers {
+                cannibals: 0,
+                missionaries: 1,
+            }
+        );
+        let Ok(Command::Solve(options)) = parse_command(&args("solve -c 2 -m 2 -b 2 -r 0,1"))
+        else {
+            panic!("expected solve command");
+        };
+        assert_eq!(
+            options.problem.rowers,
+            Some(Rowers {
+                cannibals: 0,
+                missionaries: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_command_occupancy() {
+        let Ok(Command::Solve(options)) = parse_command(&args(
+            "solve --min-occupancy 2 --load-right 3 --load-left 1..=2",
+        )) else {
+            panic!("expected solve command");
+        };
+        assert_eq!(
+            options.problem.occupancy,
+            Occupancy {
+                min_occupancy: 2,
+                right: Some((3, 3)),
+                left: Some((1, 2)),
+            }
+        );
+
+        let Ok(Command::Sweep(options)) = parse_command(&args("sweep --min-occupancy 2")) else {
+            panic!("expected sweep command");
+        };
+        assert_eq!(options.occupancy.min_occupancy, 2);
+    }
+
+    #[test]
+    fn test_parse_command_cost() {
+        let Ok(Command::Solve(options)) = parse_command(&args("solve --cost-left 3,1 -s ucs"))
+        else {
+            panic!("expected solve command");
+        };
+        assert_eq!(
+            options.problem.cost,
+            CostModel {
+                right: TripFee::default(),
+                left: TripFee {
+                    fixed: 3,
+                    per_person: 1,
+                },
+            }
+        );
+        assert!(matches!(options.strategies[..], [Strategy::UniformCost]));
+
+        let Ok(Command::Sweep(options)) = parse_command(&args("sweep --cost-right 0,2")) else {
+            panic!("expected sweep command");
+        };
+        assert_eq!(options.cost.right.per_person, 2);
+    }
+
+    #[test]
+    fn test_parse_command_certify() {
+        let Ok(Command::Certify(options)) =
+            parse_command(&args("certify -c 4 -m 4 -b 2 --minimize"))
+        else {
+            panic!("expected certify command");
+        };
+        assert_eq!(options.problem, Problem::new(4, 4, 2));
+        assert_eq!(options.certificate, None);
+        assert!(options.minimize);
+
+        let Ok(Command::Certify(options)) = parse_command(&args("certify -p proof.json")) else {
+            panic!("expected certify command");
+        };
+        assert_eq!(options.certificate.as_deref(), Some("proof.json"));
+        assert!(parse_command(&args("certify -P river")).is_err());
+    }
+
+    #[test]
+    fn test_parse_command_graph() {
+        let Ok(Command::Graph(options)) = parse_command(&args("graph -c 4 -m 4 -b 2 -s bfs"))
+        else {
+            panic!("expected graph command");
+        };
+        assert_eq!(options.problem, Problem::new(4, 4, 2));
+        assert!(matches!(options.strategy, Strategy::Bfs));
+        assert!(matches!(options.format, GraphFormat::Dot));
+
+        let Ok(Command::Graph(options)) = parse_command(&args("graph -f graphml")) else {
+            panic!("expected graph command");
+        };
+        assert!(matches!(options.format, GraphFormat::GraphMl));
+        assert!(parse_command(&args("graph -f xml")).is_err());
+        assert!(parse_command(&args("graph -s all")).is_err());
+        assert!(parse_command(&args("graph -P wolf-goat-cabbage")).is_err());
     }
 
     #[test]
-    fn test_validate_cannibal_missionary_balance() {
-        let prop = ValidateCannibalMissionaryBalanceProp {
-            cannibals_left: 1,
-            missionaries_left: 2,
-            cannibals_right: 1,
-            missionaries_right: 2,
-            cannibals_boat: 1,
-            missionaries_boat: 1,
-        };
-        assert_eq!(validate_cannibal_missionary_balance(prop), true);
-
-        let prop = ValidateCannibalMissionaryBalanceProp {
-            cannibals_left: 2,
-            missionaries_left: 1,
-            cannibals_right: 2,
-            missionaries_right: 1,
-            cannibals_boat: 1,
-            missionaries_boat: 1,
-        };
-        assert_eq!(validate_cannibal_missionary_balance(prop), false);
+    fn test_parse_command_bad_input() {
+        assert!(parse_command(&args("")).is_err());
+        assert!(parse_command(&args("launch")).is_err());
+        assert!(parse_command(&args("solve -c")).is_err());
+        assert!(parse_command(&args("solve -c three")).is_err());
+        assert!(parse_command(&args("solve -s random")).is_err());
+        assert!(parse_command(&args("solve --verbose")).is_err());
+        assert!(parse_command(&args("sweep -c 4..=1")).is_err());
+        assert!(parse_command(&args("sweep -c -1..=3")).is_err());
+        assert!(parse_command(&args("sweep -b 0..=2")).is_err());
+        assert!(parse_command(&args("sweep --cost-left -1,0")).is_err());
+        assert!(parse_command(&args("sweep -m 0..=9223372036854775807")).is_err());
+        assert!(parse_command(&args("sweep -s all")).is_err());
+        assert!(parse_command(&args("sweep -f json")).is_err());
+        assert!(parse_command(&args("verify -c 3")).is_err());
+        assert!(parse_command(&args("solve -f json -s all")).is_err());
+        assert!(parse_command(&args("solve -f json --optimal")).is_err());
+        assert!(parse_command(&args("solve -f xml")).is_err());
+        assert!(parse_command(&args("solve -P chess")).is_err());
+        assert!(parse_command(&args("solve -P wolf-goat-cabbage -f json")).is_err());
+        assert!(parse_command(&args("sweep -P wolf-goat-cabbage")).is_err());
+        assert!(parse_command(&args("solve --check shore")).is_err());
+        assert!(parse_command(&args("solve --ratio half")).is_err());
+        assert!(parse_command(&args("solve --rowers 2")).is_err());
+        assert!(parse_command(&args("solve --min-occupancy two")).is_err());
+        assert!(parse_command(&args("solve --load-right 3..=2")).is_err());
+        assert!(parse_command(&args("solve --cost-left 3")).is_err());
+        assert!(parse_command(&args("solve --cost-right 1,x")).is_err());
+        assert!(parse_command(&args("solve -P jealous-husbands -n many")).is_err());
     }
 }
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+
+use crate::plan::{Move, Plan};
+use crate::problem::{successors, CostModel, Problem, State};
+
+/// Every minimum-trip plan of an instance, stored as a layered DAG: each state
+/// reachable on a shortest path to the goal maps to the states one trip closer
+/// to the start that lead to it, together with the connecting move. Plans are
+/// priced with the instance's cost model but chosen by trip count alone.
+pub struct OptimalSolutions {
+    cost: CostModel,
+    start: State,
+    goal: State,
+    layers: Vec<Vec<State>>,
+    predecessors: HashMap<State, Vec<(State, Move)>>,
+}
+
+impl OptimalSolutions {
+    /// Number of crossings in every optimal plan.
+    pub fn trips(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    /// Number of distinct optimal plans, or `None` if it does not fit in a u128.
+    pub fn count(&self) -> Option<u128> {
+        let mut ways: HashMap<&State, u128> = HashMap::from([(&self.start, 1)]);
+        for layer in self.layers.iter().skip(1) {
+            for state in layer.iter() {
+                let mut total: u128 = 0;
+                for (previous, _) in self.predecessors[state].iter() {
+                    total = total.checked_add(*ways.get(previous).unwrap_or(&0))?;
+                }
+                ways.insert(state, total);
+            }
+        }
+        ways.get(&self.goal).copied()
+    }
+
+    /// Lazily enumerates every optimal plan without materializing them all.
+    pub fn iter(&self) -> OptimalPlans<'_> {
+        OptimalPlans {
+            solutions: self,
+            stack: vec![(&self.goal, 0)],
+            moves: Vec::new(),
+        }
+    }
+}
+
+/// Iterator over the plans of an [`OptimalSolutions`].
+pub struct OptimalPlans<'a> {
+    solutions: &'a OptimalSolutions,
+    stack: Vec<(&'a State, usize)>,
+    moves: Vec<Move>,
+}
+
+impl Iterator for OptimalPlans<'_> {
+    type Item = Plan;
+
+    fn next(&mut self) -> Option<Plan> {
+        loop {
+            let (state, index) = self.stack.last_mut()?;
+
+            if *state == &self.solutions.start {
+                let moves: Vec<Move> = self.moves.iter().rev().copied().collect();
+                let cost = self.solutions.cost.plan_cost(&moves);
+                let plan = Plan::with_cost(moves, cost);
+                self.stack.pop();
+                self.moves.pop();
+                return Some(plan);
+            }
+
+            match self.solutions.predecessors[*state].get(*index) {
+                Some((previous, movement)) => {
+                    *index += 1;
+                    self.moves.push(*movement);
+                    self.stack.push((previous, 0));
+                }
+                None => {
+                    self.stack.pop();
+                    self.moves.pop();
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn solve_all_optimal(problem: &Problem) -> Option<OptimalSolutions> {
+    let start = problem.start();
+    let goal = problem.goal();
+
+    let mut distances: HashMap<State, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut predecessors: HashMap<State, Vec<(State, Move)>> = HashMap::new();
+    let mut layers = vec![vec![start.clone()]];
+
+    while !layers.last().unwrap().contains(&goal) {
+        let trips = layers.len();
+        let mut next_layer = Vec::new();
+
+        for state in layers.last().unwrap().iter() {
+            for (next_state, movement) in successors(state, problem) {
+                match distances.get(&next_state) {
+                    Some(&distance) if distance < trips => continue,
+                    Some(_) => {}
+                    None => {
+                        distances.insert(next_state.clone(), trips);
+                        next_layer.push(next_state.clone());
+                    }
+                }
+                predecessors
+                    .entry(next_state)
+                    .or_default()
+                    .push((state.clone(), movement));
+            }
+        }
+
+        if next_layer.is_empty() {
+            return None;
+        }
+        layers.push(next_layer);
+    }
+
+    Some(OptimalSolutions {
+        cost: problem.cost,
+        start,
+        goal,
+        layers,
+        predecessors,
+    })
+}
+
+/// Number of plans that finish in exactly a given number of trips. Plans may
+/// revisit states but stop as soon as the goal is reached. A count is `None`
+/// once it no longer fits in a u128.
+pub struct PlanCounts {
+    by_trips: Vec<Option<u128>>,
+}
+
+impl PlanCounts {
+    pub fn exactly(&self, trips: usize) -> Option<u128> {
+        self.by_trips.get(trips).copied().unwrap_or(Some(0))
+    }
+
+    pub fn up_to(&self, trips: usize) -> Option<u128> {
+        self.by_trips
+            .iter()
+            .take(trips + 1)
+            .try_fold(0u128, |total, count| total.checked_add((*count)?))
+    }
+}
+
+fn add_counts(left: Option<u128>, right: Option<u128>) -> Option<u128> {
+    left?.checked_add(right?)
+}
+
+pub(crate) fn count_plans(problem: &Problem, max_trips: usize) -> PlanCounts {
+    let mut by_trips = vec![Some(0)];
+    let mut ways: HashMap<State, Option<u128>> = HashMap::from([(problem.start(), Some(1))]);
+    let mut successors_of: HashMap<State, Vec<State>> = HashMap::new();
+
+    for _ in 1..=max_trips {
+        let mut next_ways: HashMap<State, Option<u128>> = HashMap::new();
+        for (state, count) in ways.iter() {
+            if state.is_goal() {
+                continue;
+            }
+            let next_states = successors_of.entry(state.clone()).or_insert_with(|| {
+                successors(state, problem)
+                    .into_iter()
+                    .map(|(next_state, _)| next_state)
+                    .collect()
+            });
+            for next_state in next_states.iter() {
+                let total = next_ways.entry(next_state.clone()).or_insert(Some(0));
+                *total = add_counts(*total, *count);
+            }
+        }
+        by_trips.push(
+            next_ways
+                .iter()
+                .find(|(state, _)| state.is_goal())
+                .map_or(Some(0), |(_, count)| *count),
+        );
+        ways = next_ways;
+    }
+
+    PlanCounts { by_trips }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::{search, Strategy};
+
+    #[test]
+    fn test_solve_all_optimal_classic() {
+        let solutions = solve_all_optimal(&Problem::new(3, 3, 2)).unwrap();
+        let plans: Vec<Plan> = solutions.iter().collect();
+
+        assert_eq!(solutions.trips(), 11);
+        assert_eq!(solutions.count(), Some(4));
+        assert_eq!(plans.len(), 4);
+        assert!(plans.iter().all(|plan| plan.len() == 11));
+    }
+
+    #[test]
+    fn test_solve_all_optimal_matches_count() {
+        for cannibals in 0..=5 {
+            for missionaries in 0..=5 {
+                for boat_capacity in 1..=3 {
+                    let problem = Problem::new(cannibals, missionaries, boat_capacity);
+                    let expected = search(&problem, Strategy::Bfs).map(|plan| plan.len());
+                    let solutions = solve_all_optimal(&problem);
+                    assert_eq!(solutions.as_ref().map(|s| s.trips()), expected);
+
+                    if let Some(solutions) = solutions {
+                        let plans: Vec<Plan> = solutions.iter().collect();
+                        assert_eq!(Some(plans.len() as u128), solutions.count());
+                        assert!(plans.iter().all(|plan| plan.len() == solutions.trips()));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_plans_classic() {
+        let counts = count_plans(&Problem::new(3, 3, 2), 13);
+
+        assert_eq!(counts.up_to(10), Some(0));
+        assert_eq!(counts.exactly(11), Some(4));
+        assert_eq!(counts.up_to(11), Some(4));
+        assert!(counts.up_to(13).unwrap() > 4);
+        assert_eq!(counts.exactly(20), Some(0));
+    }
+
+    #[test]
+    fn test_count_plans_matches_optimal_solutions() {
+        for cannibals in 0..=5 {
+            for missionaries in 0..=5 {
+                for boat_capacity in 1..=3 {
+                    let problem = Problem::new(cannibals, missionaries, boat_capacity);
+                    let Some(solutions) = solve_all_optimal(&problem) else {
+                        continue;
+                    };
+                    let counts = count_plans(&problem, solutions.trips());
+                    assert_eq!(counts.exactly(solutions.trips()), solutions.count());
+                    assert_eq!(counts.up_to(solutions.trips()), solutions.count());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_plans_overflow() {
+        let counts = count_plans(&Problem::new(10, 20, 3), 400);
+
+        assert_eq!(counts.exactly(29), Some(13877538141));
+        assert_eq!(counts.up_to(400), None);
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+
+use crate::problem::Rowers;
+
+/// A single crossing: how many of each group ride the boat and which way.
+/// `rowers` counts the people in the boat who can row; when everybody can row
+/// it matches the two boat counts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Move {
+    pub cannibals_boat: i64,
+    pub missionaries_boat: i64,
+    pub move_right: bool,
+    pub rowers: Rowers,
+}
+
+impl Move {
+    /// A crossing where everybody in the boat can row.
+    pub fn new(cannibals_boat: i64, missionaries_boat: i64, move_right: bool) -> Move {
+        Move {
+            cannibals_boat,
+            missionaries_boat,
+            move_right,
+            rowers: Rowers {
+                cannibals: cannibals_boat,
+                missionaries: missionaries_boat,
+            },
+        }
+    }
+}
+
+// Written as `right C M`, the plain plan format, followed by the rowing
+// cannibals and missionaries when not everybody in the boat rows.
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            if self.move_right { "right" } else { "left" },
+            self.cannibals_boat,
+            self.missionaries_boat
+        )?;
+        if self.rowers.cannibals != self.cannibals_boat
+            || self.rowers.missionaries != self.missionaries_boat
+        {
+            write!(f, " {} {}", self.rowers.cannibals, self.rowers.missionaries)?;
+        }
+        Ok(())
+    }
+}
+
+/// A sequence of actions taking a puzzle from its start to its goal, together
+/// with their total cost. For missionaries and cannibals the actions are
+/// crossings, priced by the problem's [`CostModel`](crate::CostModel).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Plan<A = Move> {
+    moves: Vec<A>,
+    cost: i64,
+}
+
+impl<A> Plan<A> {
+    /// A plan whose actions all cost 1.
+    pub fn new(moves: Vec<A>) -> Plan<A> {
+        let cost = moves.len() as i64;
+        Plan { moves, cost }
+    }
+
+    pub fn with_cost(moves: Vec<A>, cost: i64) -> Plan<A> {
+        Plan { moves, cost }
+    }
+
+    pub fn moves(&self) -> &[A] {
+        &self.moves
+    }
+
+    /// Number of actions (crossings) in the plan.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Sum of the costs of every action.
+    pub fn cost(&self) -> i64 {
+        self.cost
+    }
+
+    pub fn into_moves(self) -> Vec<A> {
+        self.moves
+    }
+}
@@ -0,0 +1,983 @@
+use std::cmp;
+use std::hash::{Hash, Hasher};
+
+use crate::error::SolveError;
+use crate::optimal::{count_plans, solve_all_optimal, OptimalSolutions, PlanCounts};
+use crate::plan::{Move, Plan};
+use crate::search::{search, SearchProblem, Strategy};
+
+/// An instance of the puzzle: everybody starts on the left bank together with
+/// the boat and has to be ferried to the right bank.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Problem {
+    pub cannibals: i64,
+    pub missionaries: i64,
+    pub boat_capacity: i64,
+    pub safety: SafetyRule,
+    /// Who can row; `None` means everybody.
+    pub rowers: Option<Rowers>,
+    pub occupancy: Occupancy,
+    pub cost: CostModel,
+}
+
+/// How many people may ride the boat on one crossing. `min_occupancy`
+/// applies in both directions unless a direction has its own inclusive
+/// `(min, max)` bounds. The boat capacity always caps the maximum, and the
+/// boat never crosses empty.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Occupancy {
+    pub min_occupancy: i64,
+    pub right: Option<(i64, i64)>,
+    pub left: Option<(i64, i64)>,
+}
+
+impl Default for Occupancy {
+    fn default() -> Occupancy {
+        Occupancy {
+            min_occupancy: 1,
+            right: None,
+            left: None,
+        }
+    }
+}
+
+impl Occupancy {
+    /// The inclusive bounds on the people in the boat for a crossing.
+    pub fn bounds(&self, move_right: bool, boat_capacity: i64) -> (i64, i64) {
+        let limits = if move_right { self.right } else { self.left };
+        let (min, max) = limits.unwrap_or((self.min_occupancy, boat_capacity));
+        (cmp::max(min, 1), cmp::min(max, boat_capacity))
+    }
+}
+
+/// What one crossing costs: a fixed fee plus a fee per person aboard, set for
+/// each direction so that rowing against the current can cost more. The
+/// default charges 1 per trip, which makes the cost of a plan its trip count.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CostModel {
+    pub right: TripFee,
+    pub left: TripFee,
+}
+
+/// The fees of a crossing in one direction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TripFee {
+    pub fixed: i64,
+    pub per_person: i64,
+}
+
+impl Default for TripFee {
+    fn default() -> TripFee {
+        TripFee {
+            fixed: 1,
+            per_person: 0,
+        }
+    }
+}
+
+impl CostModel {
+    /// The same fees in both directions.
+    pub fn uniform(fee: TripFee) -> CostModel {
+        CostModel {
+            right: fee,
+            left: fee,
+        }
+    }
+
+    /// The fees of a crossing in the given direction.
+    pub fn fee(&self, move_right: bool) -> TripFee {
+        if move_right {
+            self.right
+        } else {
+            self.left
+        }
+    }
+
+    /// The cost of one crossing.
+    pub fn trip_cost(&self, movement: &Move) -> i64 {
+        let fee = self.fee(movement.move_right);
+        fee.fixed + fee.per_person * (movement.cannibals_boat + movement.missionaries_boat)
+    }
+
+    /// The total cost of a plan's crossings.
+    pub fn plan_cost(&self, moves: &[Move]) -> i64 {
+        moves.iter().map(|movement| self.trip_cost(movement)).sum()
+    }
+}
+
+/// How many people of each group can row. The others only ride along, and
+/// every crossing needs at least one rower in the boat.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Rowers {
+    pub cannibals: i64,
+    pub missionaries: i64,
+}
+
+/// Where cannibals must not outnumber missionaries, and by how much they may.
+/// A place is safe when it has no missionaries or at most
+/// `ratio * missionaries + surplus` cannibals. The default is the classic
+/// rule: no surplus at all, checked on both banks and in the boat.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SafetyRule {
+    pub left_bank: bool,
+    pub right_bank: bool,
+    /// Whether the people in the boat are checked during a crossing. Without
+    /// it the rule only applies on the banks while the boat is docked.
+    pub boat: bool,
+    pub surplus: i64,
+    pub ratio: i64,
+}
+
+impl Default for SafetyRule {
+    fn default() -> SafetyRule {
+        SafetyRule {
+            left_bank: true,
+            right_bank: true,
+            boat: true,
+            surplus: 0,
+            ratio: 1,
+        }
+    }
+}
+
+impl SafetyRule {
+    /// Whether `cannibals` may be together with `missionaries`.
+    pub fn allows(&self, cannibals: i64, missionaries: i64) -> bool {
+        missionaries == 0
+            || cannibals
+                <= self
+                    .ratio
+                    .saturating_mul(missionaries)
+                    .saturating_add(self.surplus)
+    }
+}
+
+impl Problem {
+    pub fn new(cannibals: i64, missionaries: i64, boat_capacity: i64) -> Problem {
+        Problem {
+            cannibals,
+            missionaries,
+            boat_capacity,
+            safety: SafetyRule::default(),
+            rowers: None,
+            occupancy: Occupancy::default(),
+            cost: CostModel::default(),
+        }
+    }
+
+    /// The same instance under a different safety rule.
+    pub fn with_safety(self, safety: SafetyRule) -> Problem {
+        Problem { safety, ..self }
+    }
+
+    /// The same instance where only `rowers` can row.
+    pub fn with_rowers(self, rowers: Rowers) -> Problem {
+        Problem {
+            rowers: Some(rowers),
+            ..self
+        }
+    }
+
+    /// The same instance with different limits on the boat's occupancy.
+    pub fn with_occupancy(self, occupancy: Occupancy) -> Problem {
+        Problem { occupancy, ..self }
+    }
+
+    /// The same instance with crossings priced by `cost`.
+    pub fn with_cost(self, cost: CostModel) -> Problem {
+        Problem { cost, ..self }
+    }
+
+    // The cheapest any crossing can be, given who has to be in the boat.
+    fn cheapest_trip(&self) -> i64 {
+        [true, false]
+            .into_iter()
+            .map(|move_right| {
+                let fee = self.cost.fee(move_right);
+                let (min_people, _) = self.occupancy.bounds(move_right, self.boat_capacity);
+                fee.fixed + fee.per_person * min_people
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    // The rowers of each group, counting everybody when no rowers are set.
+    pub(crate) fn all_rowers(&self) -> Rowers {
+        self.rowers.unwrap_or(Rowers {
+            cannibals: self.cannibals,
+            missionaries: self.missionaries,
+        })
+    }
+
+    /// Checks that the parameters describe a puzzle that can be searched.
+    pub fn validate(&self) -> Result<(), SolveError> {
+        if self.cannibals < 0 || self.missionaries < 0 {
+            return Err(SolveError::NegativeCount);
+        }
+        if self.boat_capacity <= 0 {
+            return Err(SolveError::ZeroCapacity);
+        }
+        if let Some(rowers) = self.rowers {
+            if rowers.cannibals < 0 || rowers.missionaries < 0 {
+                return Err(SolveError::NegativeCount);
+            }
+            if rowers.cannibals > self.cannibals || rowers.missionaries > self.missionaries {
+                return Err(SolveError::TooManyRowers);
+            }
+        }
+        // The heuristic and bank arithmetic need up to twice the head count.
+        self.cannibals
+            .checked_add(self.missionaries)
+            .and_then(|people| people.checked_mul(2))
+            .and_then(|people| people.checked_add(1))
+            .ok_or(SolveError::Overflow)?;
+        let fees = [self.cost.right, self.cost.left];
+        if fees.iter().any(|fee| fee.fixed < 0 || fee.per_person < 0) {
+            return Err(SolveError::NegativeCost);
+        }
+        // No cheapest plan visits a state twice, and no trip costs more than
+        // a full boat. The rowers on the left only add to the states when
+        // they are not simply everybody on the left.
+        let mut counts = vec![self.cannibals, self.missionaries];
+        if let Some(rowers) = self.rowers {
+            counts.extend([rowers.cannibals, rowers.missionaries]);
+        }
+        let states = counts
+            .into_iter()
+            .try_fold(2i64, |states, count| states.checked_mul(count + 1));
+        fees.iter()
+            .map(|fee| {
+                fee.per_person
+                    .checked_mul(self.boat_capacity)
+                    .and_then(|cost| cost.checked_add(fee.fixed))
+                    .and_then(|cost| cost.checked_mul(states?))
+            })
+            .collect::<Option<Vec<i64>>>()
+            .ok_or(SolveError::Overflow)?;
+        if self.safety.left_bank && !self.safety.allows(self.cannibals, self.missionaries) {
+            return Err(SolveError::UnsafeInitialState);
+        }
+        Ok(())
+    }
+
+    /// Searches for a plan with the given strategy. `Ok(None)` means the
+    /// puzzle has no solution.
+    pub fn solve(&self, strategy: Strategy) -> Result<Option<Plan>, SolveError> {
+        self.validate()?;
+        Ok(search(self, strategy))
+    }
+
+    /// Finds every plan of minimum length, or `Ok(None)` if there is none.
+    pub fn solve_all_optimal(&self) -> Result<Option<OptimalSolutions>, SolveError> {
+        self.validate()?;
+        Ok(solve_all_optimal(self))
+    }
+
+    /// Counts the plans of every length up to `max_trips` without listing them.
+    pub fn count_plans(&self, max_trips: usize) -> Result<PlanCounts, SolveError> {
+        self.validate()?;
+        Ok(count_plans(self, max_trips))
+    }
+
+    pub(crate) fn start(&self) -> State {
+        State {
+            cannibals_left: self.cannibals,
+            missionaries_left: self.missionaries,
+            boat_left: true,
+            rowers_left: self.all_rowers(),
+        }
+    }
+
+    pub(crate) fn goal(&self) -> State {
+        State {
+            cannibals_left: 0,
+            missionaries_left: 0,
+            boat_left: false,
+            rowers_left: Rowers::default(),
+        }
+    }
+}
+
+// Every crossing costs one trip, and `estimate_trips` bounds the rest.
+impl SearchProblem for Problem {
+    type State = State;
+    type Action = Move;
+
+    fn initial_state(&self) -> State {
+        self.start()
+    }
+
+    fn successors(&self, state: &State) -> Vec<(State, Move, i64)> {
+        successors(state, self)
+            .into_iter()
+            .map(|(next_state, movement)| {
+                let cost = self.cost.trip_cost(&movement);
+                (next_state, movement, cost)
+            })
+            .collect()
+    }
+
+    fn is_goal(&self, state: &State) -> bool {
+        state.is_goal()
+    }
+
+    // Every trip left costs at least the cheapest one.
+    fn heuristic(&self, state: &State) -> i64 {
+        estimate_trips(state, self.boat_capacity) * self.cheapest_trip()
+    }
+}
+
+/// Where everybody is between two crossings, described by the left bank.
+/// `rowers_left` counts the people on the left who can row; when everybody
+/// can row it matches the other two counts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct State {
+    pub cannibals_left: i64,
+    pub missionaries_left: i64,
+    pub boat_left: bool,
+    pub rowers_left: Rowers,
+}
+
+impl State {
+    /// Whether everybody has crossed to the right bank.
+    pub fn is_goal(&self) -> bool {
+        self.cannibals_left == 0 && self.missionaries_left == 0 && !self.boat_left
+    }
+}
+
+/// Whether `problem` has a solution, by the closed form known for the
+/// classic rules with as many cannibals as missionaries: a boat for two
+/// carries up to three pairs, a boat for three up to five, and a boat for
+/// four or more any number. Returns `None` for any other problem, or one
+/// that does not validate.
+pub fn is_solvable_analytic(problem: &Problem) -> Option<bool> {
+    if problem.validate().is_err()
+        || problem.cannibals != problem.missionaries
+        || problem.safety != SafetyRule::default()
+        || problem.rowers.is_some()
+        || problem.occupancy != Occupancy::default()
+    {
+        return None;
+    }
+    // Nobody can take the boat over without somebody to row, and a boat for
+    // one has to be rowed back by whoever just crossed.
+    let pairs = problem.missionaries;
+    Some(match problem.boat_capacity {
+        _ if pairs == 0 => false,
+        1 => false,
+        2 => pairs <= 3,
+        3 => pairs <= 5,
+        _ => true,
+    })
+}
+
+impl Hash for State {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cannibals_left.hash(state);
+        self.missionaries_left.hash(state);
+        self.boat_left.hash(state);
+        self.rowers_left.hash(state);
+    }
+}
+
+pub(crate) fn score(cannibals_left: i64, missionaries_left: i64) -> i64 {
+    cannibals_left + missionaries_left
+}
+
+// Lower bound on the trips still needed from `state`, ignoring the balance
+// rule: every round trip moves at most `boat_capacity - 1` people across net,
+// and the final trip moves at most `boat_capacity`. This is the exact distance
+// in a relaxed puzzle, so it is admissible and consistent.
+pub(crate) fn estimate_trips(state: &State, boat_capacity: i64) -> i64 {
+    let people_left = score(state.cannibals_left, state.missionaries_left);
+    estimate_crossings(people_left, state.boat_left, boat_capacity)
+}
+
+// The same bound for any puzzle where `people_left` people and a boat of
+// `boat_capacity` seats have to end up on the right bank.
+pub(crate) fn estimate_crossings(people_left: i64, boat_left: bool, boat_capacity: i64) -> i64 {
+    fn trips_from_left(people_left: i64, boat_capacity: i64) -> i64 {
+        if people_left == 0 {
+            0
+        } else if people_left <= boat_capacity || boat_capacity <= 1 {
+            1
+        } else {
+            let round_trips = (people_left - 2) / (boat_capacity - 1);
+            2 * round_trips + 1
+        }
+    }
+
+    if boat_left {
+        trips_from_left(people_left, boat_capacity)
+    } else if people_left == 0 {
+        0
+    } else {
+        1 + trips_from_left(people_left + 1, boat_capacity)
+    }
+}
+
+pub(crate) struct ValidateCannibalMissionaryBalanceProp {
+    pub(crate) cannibals_left: i64,
+    pub(crate) missionaries_left: i64,
+    pub(crate) cannibals_right: i64,
+    pub(crate) missionaries_right: i64,
+    pub(crate) cannibals_boat: i64,
+    pub(crate) missionaries_boat: i64,
+}
+
+pub(crate) fn validate_cannibal_missionary_balance(
+    prop: ValidateCannibalMissionaryBalanceProp,
+    rule: &SafetyRule,
+) -> bool {
+    let left_side_balance =
+        !rule.left_bank || rule.allows(prop.cannibals_left, prop.missionaries_left);
+    let right_side_balance =
+        !rule.right_bank || rule.allows(prop.cannibals_right, prop.missionaries_right);
+    let boat_balance = !rule.boat || rule.allows(prop.cannibals_boat, prop.missionaries_boat);
+
+    left_side_balance && right_side_balance && boat_balance
+}
+
+pub(crate) fn successors(state: &State, problem: &Problem) -> Vec<(State, Move)> {
+    let boat_capacity = problem.boat_capacity;
+    let cannibals_left = state.cannibals_left;
+    let missionaries_left = state.missionaries_left;
+    let cannibals_right = problem.cannibals - cannibals_left;
+    let missionaries_right = problem.missionaries - missionaries_left;
+
+    let max_cannibals_on_boat = if state.boat_left {
+        cmp::min(boat_capacity, cannibals_left)
+    } else {
+        cmp::min(boat_capacity, cannibals_right)
+    };
+
+    let max_missionaries_on_boat = |cannibals_boat: i64| {
+        if state.boat_left {
+            cmp::min(boat_capacity - cannibals_boat, missionaries_left)
+        } else {
+            cmp::min(boat_capacity - cannibals_boat, missionaries_right)
+        }
+    };
+
+    let all_rowers = problem.all_rowers();
+    let departing = if state.boat_left {
+        state.rowers_left
+    } else {
+        Rowers {
+            cannibals: all_rowers.cannibals - state.rowers_left.cannibals,
+            missionaries: all_rowers.missionaries - state.rowers_left.missionaries,
+        }
+    };
+
+    let (min_people, max_people) = problem.occupancy.bounds(state.boat_left, boat_capacity);
+
+    let mut next_states = Vec::new();
+
+    for cannibals_boat in 0..=max_cannibals_on_boat {
+        for missionaries_boat in 0..=max_missionaries_on_boat(cannibals_boat) {
+            let people = cannibals_boat + missionaries_boat;
+            if people == 0 || people < min_people || people > max_people {
+                continue;
+            }
+
+            fn update_counts(left: i64, right: i64, boat: i64, boat_left: bool) -> (i64, i64) {
+                if boat_left {
+                    (left - boat, right + boat)
+                } else {
+                    (left + boat, right - boat)
+                }
+            }
+
+            let (next_cannibals_left, next_cannibals_right) = update_counts(
+                cannibals_left,
+                cannibals_right,
+                cannibals_boat,
+                state.boat_left,
+            );
+            let (next_missionaries_left, next_missionaries_right) = update_counts(
+                missionaries_left,
+                missionaries_right,
+                missionaries_boat,
+                state.boat_left,
+            );
+
+            if !validate_cannibal_missionary_balance(
+                ValidateCannibalMissionaryBalanceProp {
+                    cannibals_left: next_cannibals_left,
+                    missionaries_left: next_missionaries_left,
+                    cannibals_right: next_cannibals_right,
+                    missionaries_right: next_missionaries_right,
+                    cannibals_boat,
+                    missionaries_boat,
+                },
+                &problem.safety,
+            ) {
+                continue;
+            }
+
+            // Rowers and passengers of a group are otherwise alike, so a
+            // crossing is only told apart by how many of each row.
+            let rowing_cannibals_range = rowing_range(
+                cannibals_boat,
+                departing.cannibals,
+                if state.boat_left {
+                    cannibals_left
+                } else {
+                    cannibals_right
+                },
+            );
+            for rowing_cannibals in rowing_cannibals_range {
+                let rowing_missionaries_range = rowing_range(
+                    missionaries_boat,
+                    departing.missionaries,
+                    if state.boat_left {
+                        missionaries_left
+                    } else {
+                        missionaries_right
+                    },
+                );
+                for rowing_missionaries in rowing_missionaries_range {
+                    if rowing_cannibals + rowing_missionaries == 0 {
+                        continue;
+                    }
+                    let rowers = Rowers {
+                        cannibals: rowing_cannibals,
+                        missionaries: rowing_missionaries,
+                    };
+                    next_states.push((
+                        State {
+                            cannibals_left: next_cannibals_left,
+                            missionaries_left: next_missionaries_left,
+                            boat_left: !state.boat_left,
+                            rowers_left: move_rowers(state, rowers),
+                        },
+                        Move {
+                            cannibals_boat,
+                            missionaries_boat,
+                            move_right: state.boat_left,
+                            rowers,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+    next_states
+}
+
+// How many of `boat` people of a group can be rowers when the departing bank
+// has `people` of that group, `rowers` of whom can row.
+fn rowing_range(boat: i64, rowers: i64, people: i64) -> std::ops::RangeInclusive<i64> {
+    let passengers = people - rowers;
+    cmp::max(0, boat - passengers)..=cmp::min(boat, rowers)
+}
+
+pub(crate) fn move_rowers(state: &State, rowers: Rowers) -> Rowers {
+    let sign = if state.boat_left { -1 } else { 1 };
+    Rowers {
+        cannibals: state.rowers_left.cannibals + sign * rowers.cannibals,
+        missionaries: state.rowers_left.missionaries + sign * rowers.missionaries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_cannibal_missionary_balance() {
+        let prop = ValidateCannibalMissionaryBalanceProp {
+            cannibals_left: 1,
+            missionaries_left: 2,
+            cannibals_right: 1,
+            missionaries_right: 2,
+            cannibals_boat: 1,
+            missionaries_boat: 1,
+        };
+        assert!(validate_cannibal_missionary_balance(
+            prop,
+            &SafetyRule::default()
+        ));
+
+        let prop = ValidateCannibalMissionaryBalanceProp {
+            cannibals_left: 2,
+            missionaries_left: 1,
+            cannibals_right: 2,
+            missionaries_right: 1,
+            cannibals_boat: 1,
+            missionaries_boat: 1,
+        };
+        assert!(!validate_cannibal_missionary_balance(
+            prop,
+            &SafetyRule::default()
+        ));
+    }
+
+    fn balance(
+        (cannibals_left, missionaries_left): (i64, i64),
+        (cannibals_right, missionaries_right): (i64, i64),
+        (cannibals_boat, missionaries_boat): (i64, i64),
+        rule: &SafetyRule,
+    ) -> bool {
+        validate_cannibal_missionary_balance(
+            ValidateCannibalMissionaryBalanceProp {
+                cannibals_left,
+                missionaries_left,
+                cannibals_right,
+                missionaries_right,
+                cannibals_boat,
+                missionaries_boat,
+            },
+            rule,
+        )
+    }
+
+    #[test]
+    fn test_safety_rule_locations() {
+        let boat_exempt = SafetyRule {
+            boat: false,
+            ..SafetyRule::default()
+        };
+        assert!(!balance((1, 1), (1, 1), (2, 1), &SafetyRule::default()));
+        assert!(balance((1, 1), (1, 1), (2, 1), &boat_exempt));
+        assert!(!balance((2, 1), (1, 1), (0, 1), &boat_exempt));
+
+        let right_bank_only = SafetyRule {
+            left_bank: false,
+            boat: false,
+            ..SafetyRule::default()
+        };
+        assert!(balance((3, 1), (0, 2), (2, 1), &right_bank_only));
+        assert!(!balance((0, 2), (3, 1), (0, 1), &right_bank_only));
+    }
+
+    #[test]
+    fn test_safety_rule_surplus_and_ratio() {
+        let surplus = SafetyRule {
+            surplus: 1,
+            ..SafetyRule::default()
+        };
+        assert!(surplus.allows(3, 2));
+        assert!(!surplus.allows(4, 2));
+        assert!(surplus.allows(7, 0));
+
+        let ratio = SafetyRule {
+            ratio: 2,
+            ..SafetyRule::default()
+        };
+        assert!(ratio.allows(4, 2));
+        assert!(!ratio.allows(5, 2));
+
+        let saturating = SafetyRule {
+            ratio: i64::MAX,
+            surplus: i64::MAX,
+            ..SafetyRule::default()
+        };
+        assert!(saturating.allows(i64::MAX, 2));
+    }
+
+    #[test]
+    fn test_safety_rule_changes_solvability() {
+        // 4/4/2 has no solution under the classic rule.
+        assert_eq!(
+            search(&Problem::new(4, 4, 2), Strategy::Bfs).map(|plan| plan.len()),
+            None
+        );
+
+        let surplus = Problem::new(4, 4, 2).with_safety(SafetyRule {
+            surplus: 1,
+            ..SafetyRule::default()
+        });
+        assert!(search(&surplus, Strategy::Bfs).is_some());
+
+        let boat_exempt = Problem::new(3, 3, 2).with_safety(SafetyRule {
+            boat: false,
+            ..SafetyRule::default()
+        });
+        assert_eq!(
+            search(&boat_exempt, Strategy::Bfs).map(|plan| plan.len()),
+            Some(11)
+        );
+
+        let unchecked = Problem::new(4, 4, 2).with_safety(SafetyRule {
+            left_bank: false,
+            right_bank: false,
+            boat: false,
+            ..SafetyRule::default()
+        });
+        assert_eq!(
+            search(&unchecked, Strategy::Bfs).map(|plan| plan.len()),
+            Some(13)
+        );
+    }
+
+    #[test]
+    fn test_estimate_trips() {
+        let state = State {
+            cannibals_left: 3,
+            missionaries_left: 3,
+            boat_left: true,
+            rowers_left: Rowers::default(),
+        };
+        assert_eq!(estimate_trips(&state, 2), 9);
+
+        let state = State {
+            cannibals_left: 1,
+            missionaries_left: 0,
+            boat_left: false,
+            rowers_left: Rowers::default(),
+        };
+        assert_eq!(estimate_trips(&state, 2), 2);
+
+        let state = State {
+            cannibals_left: 0,
+            missionaries_left: 0,
+            boat_left: false,
+            rowers_left: Rowers::default(),
+        };
+        assert_eq!(estimate_trips(&state, 2), 0);
+    }
+
+    #[test]
+    fn test_rowers() {
+        let rowers = |cannibals, missionaries| Rowers {
+            cannibals,
+            missionaries,
+        };
+        let trips = |problem: Problem| search(&problem, Strategy::Bfs).map(|plan| plan.len());
+
+        assert_eq!(
+            trips(Problem::new(3, 3, 2).with_rowers(rowers(3, 3))),
+            Some(11)
+        );
+        assert_eq!(trips(Problem::new(3, 3, 2).with_rowers(rowers(0, 0))), None);
+        // A single rowing cannibal has to ferry everybody else.
+        assert_eq!(trips(Problem::new(2, 2, 2).with_rowers(rowers(1, 0))), None);
+        assert!(trips(Problem::new(3, 3, 2).with_rowers(rowers(1, 3))).is_some());
+
+        for (cannibals, missionaries) in [(0, 1), (1, 1), (1, 0), (2, 2), (0, 3)] {
+            let problem = Problem::new(3, 3, 2).with_rowers(rowers(cannibals, missionaries));
+            let Some(plan) = search(&problem, Strategy::AStar) else {
+                continue;
+            };
+            assert!(plan
+                .moves()
+                .iter()
+                .all(|movement| { movement.rowers.cannibals + movement.rowers.missionaries > 0 }));
+        }
+    }
+
+    #[test]
+    fn test_occupancy_bounds() {
+        let occupancy = Occupancy {
+            min_occupancy: 2,
+            right: None,
+            left: Some((0, 5)),
+        };
+        assert_eq!(occupancy.bounds(true, 3), (2, 3));
+        assert_eq!(occupancy.bounds(false, 3), (1, 3));
+        assert_eq!(Occupancy::default().bounds(true, 4), (1, 4));
+    }
+
+    #[test]
+    fn test_occupancy_changes_solvability() {
+        let trips = |problem: Problem| search(&problem, Strategy::Bfs).map(|plan| plan.len());
+        let min_occupancy = |min_occupancy| Occupancy {
+            min_occupancy,
+            ..Occupancy::default()
+        };
+
+        // Two people shuttling back and forth never make progress.
+        assert_eq!(trips(Problem::new(3, 3, 2)), Some(11));
+        assert_eq!(
+            trips(Problem::new(3, 3, 2).with_occupancy(min_occupancy(2))),
+            None
+        );
+        assert_eq!(trips(Problem::new(3, 3, 3)), Some(5));
+        assert_eq!(
+            trips(Problem::new(3, 3, 3).with_occupancy(min_occupancy(2))),
+            Some(7)
+        );
+        assert_eq!(
+            trips(Problem::new(2, 2, 2).with_occupancy(min_occupancy(2))),
+            None
+        );
+
+        // A full boat on every outward journey still leaves room to send
+        // two back, but not if only one may return.
+        let full_outward = Occupancy {
+            right: Some((3, 3)),
+            ..Occupancy::default()
+        };
+        assert_eq!(
+            trips(Problem::new(3, 3, 3).with_occupancy(full_outward)),
+            Some(5)
+        );
+        let single_return = Occupancy {
+            left: Some((1, 1)),
+            ..full_outward
+        };
+        assert_eq!(
+            trips(Problem::new(3, 3, 3).with_occupancy(single_return)),
+            None
+        );
+        assert_eq!(
+            trips(Problem::new(2, 2, 3).with_occupancy(single_return)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cost_model() {
+        let cost = CostModel {
+            right: TripFee {
+                fixed: 2,
+                per_person: 1,
+            },
+            left: TripFee {
+                fixed: 5,
+                per_person: 0,
+            },
+        };
+        assert_eq!(cost.trip_cost(&Move::new(1, 1, true)), 4);
+        assert_eq!(cost.trip_cost(&Move::new(1, 1, false)), 5);
+
+        let plan = Problem::new(3, 3, 2)
+            .solve(Strategy::AStar)
+            .unwrap()
+            .unwrap();
+        assert_eq!(plan.cost(), 11);
+        assert_eq!(CostModel::default().plan_cost(plan.moves()), 11);
+    }
+
+    #[test]
+    fn test_cheapest_plan() {
+        // Every shortest plan takes 9 trips, but they send back between 5 and
+        // 7 people.
+        let problem = Problem::new(4, 4, 3).with_cost(CostModel {
+            left: TripFee {
+                fixed: 0,
+                per_person: 1,
+            },
+            right: TripFee {
+                fixed: 0,
+                per_person: 0,
+            },
+        });
+        let costs: Vec<i64> = problem
+            .solve_all_optimal()
+            .unwrap()
+            .unwrap()
+            .iter()
+            .map(|plan| plan.cost())
+            .collect();
+        assert_eq!(costs.iter().min(), Some(&5));
+        assert_eq!(costs.iter().max(), Some(&7));
+
+        for strategy in [Strategy::AStar, Strategy::UniformCost] {
+            let plan = problem.solve(strategy).unwrap().unwrap();
+            assert_eq!((plan.len(), plan.cost()), (9, 5));
+        }
+        for strategy in Strategy::ALL {
+            let plan = problem.solve(strategy).unwrap().unwrap();
+            assert_eq!(plan.cost(), problem.cost.plan_cost(plan.moves()));
+            assert!(plan.cost() >= 5);
+        }
+    }
+
+    #[test]
+    fn test_validate_problem() {
+        assert_eq!(Problem::new(3, 3, 2).validate(), Ok(()));
+        assert_eq!(
+            Problem::new(-1, 3, 2).validate(),
+            Err(SolveError::NegativeCount)
+        );
+        assert_eq!(
+            Problem::new(3, -3, 2).validate(),
+            Err(SolveError::NegativeCount)
+        );
+        assert_eq!(
+            Problem::new(3, 3, 0).validate(),
+            Err(SolveError::ZeroCapacity)
+        );
+        assert_eq!(
+            Problem::new(3, 3, -2).validate(),
+            Err(SolveError::ZeroCapacity)
+        );
+        assert_eq!(
+            Problem::new(4, 3, 2).validate(),
+            Err(SolveError::UnsafeInitialState)
+        );
+        let right_bank_only = SafetyRule {
+            left_bank: false,
+            ..SafetyRule::default()
+        };
+        assert_eq!(
+            Problem::new(4, 3, 2)
+                .with_safety(right_bank_only)
+                .validate(),
+            Ok(())
+        );
+        assert_eq!(
+            Problem::new(3, 3, 2)
+                .with_rowers(Rowers {
+                    cannibals: 4,
+                    missionaries: 0,
+                })
+                .validate(),
+            Err(SolveError::TooManyRowers)
+        );
+        assert_eq!(
+            Problem::new(3, 3, 2)
+                .with_rowers(Rowers {
+                    cannibals: 0,
+                    missionaries: -1,
+                })
+                .validate(),
+            Err(SolveError::NegativeCount)
+        );
+        assert_eq!(
+            Problem::new(i64::MAX, 1, 2).validate(),
+            Err(SolveError::Overflow)
+        );
+        assert_eq!(
+            Problem::new(i64::MAX / 2, i64::MAX / 2, 2).validate(),
+            Err(SolveError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_validate_problem_edge_cases() {
+        // Cannibals alone are never outnumbering anybody.
+        assert_eq!(Problem::new(5, 0, 2).validate(), Ok(()));
+        assert_eq!(Problem::new(0, 0, 1).validate(), Ok(()));
+        assert_eq!(Problem::new(3, 3, i64::MAX).validate(), Ok(()));
+
+        let fee = |fixed, per_person| CostModel::uniform(TripFee { fixed, per_person });
+        assert_eq!(
+            Problem::new(3, 3, 2).with_cost(fee(-1, 0)).validate(),
+            Err(SolveError::NegativeCost)
+        );
+        assert_eq!(
+            Problem::new(3, 3, 2).with_cost(fee(0, i64::MAX)).validate(),
+            Err(SolveError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_validate_large_problem_without_rowers() {
+        let problem = Problem::new(60_000, 60_000, 4);
+        assert_eq!(problem.validate(), Ok(()));
+        assert!(problem.solve(Strategy::AStar).unwrap().is_some());
+
+        // Designated rowers multiply the states that have to be priced.
+        let rowers = Rowers {
+            cannibals: 60_000,
+            missionaries: 60_000,
+        };
+        assert_eq!(
+            problem.with_rowers(rowers).validate(),
+            Err(SolveError::Overflow)
+        );
+    }
+}
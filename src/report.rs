@@ -0,0 +1,497 @@
+use crate::json::{Json, JsonError};
+use crate::plan::{Move, Plan};
+use crate::problem::{CostModel, Occupancy, Problem, Rowers, SafetyRule, State, TripFee};
+use crate::search::Strategy;
+use crate::verify::check_move;
+
+/// JSON schema of the documents written by [`Solution::to_json`].
+pub const SOLUTION_SCHEMA: &str = include_str!("../schema/solution.schema.json");
+
+/// The outcome of solving a problem with a strategy, in a form that can be
+/// written to and read back from JSON.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Solution {
+    pub problem: Problem,
+    pub strategy: Strategy,
+    pub plan: Option<Plan>,
+}
+
+fn side(left: bool) -> Json {
+    Json::String(if left { "left" } else { "right" }.to_string())
+}
+
+fn bank_json(cannibals: i64, missionaries: i64) -> Json {
+    Json::object(vec![
+        ("cannibals", Json::Number(cannibals)),
+        ("missionaries", Json::Number(missionaries)),
+    ])
+}
+
+pub(crate) fn state_json(problem: &Problem, state: &State) -> Json {
+    Json::object(vec![
+        (
+            "left",
+            bank_json(state.cannibals_left, state.missionaries_left),
+        ),
+        (
+            "right",
+            bank_json(
+                problem.cannibals - state.cannibals_left,
+                problem.missionaries - state.missionaries_left,
+            ),
+        ),
+        ("boat", side(state.boat_left)),
+    ])
+}
+
+fn safety_json(safety: &SafetyRule) -> Json {
+    Json::object(vec![
+        ("left_bank", Json::Bool(safety.left_bank)),
+        ("right_bank", Json::Bool(safety.right_bank)),
+        ("boat", Json::Bool(safety.boat)),
+        ("surplus", Json::Number(safety.surplus)),
+        ("ratio", Json::Number(safety.ratio)),
+    ])
+}
+
+fn parse_safety(problem: &Json) -> Result<SafetyRule, JsonError> {
+    let value = problem.get("safety")?;
+    Ok(SafetyRule {
+        left_bank: value.get("left_bank")?.as_bool()?,
+        right_bank: value.get("right_bank")?.as_bool()?,
+        boat: value.get("boat")?.as_bool()?,
+        surplus: value.get("surplus")?.as_i64()?,
+        ratio: value.get("ratio")?.as_i64()?,
+    })
+}
+
+pub(crate) fn rowers_json(rowers: &Rowers) -> Json {
+    bank_json(rowers.cannibals, rowers.missionaries)
+}
+
+pub(crate) fn parse_rowers(value: &Json) -> Result<Rowers, JsonError> {
+    Ok(Rowers {
+        cannibals: value.get("cannibals")?.as_i64()?,
+        missionaries: value.get("missionaries")?.as_i64()?,
+    })
+}
+
+fn occupancy_json(occupancy: &Occupancy) -> Json {
+    let mut fields = vec![("min_occupancy", Json::Number(occupancy.min_occupancy))];
+    for (key, limits) in [("right", occupancy.right), ("left", occupancy.left)] {
+        if let Some((min, max)) = limits {
+            fields.push((
+                key,
+                Json::object(vec![("min", Json::Number(min)), ("max", Json::Number(max))]),
+            ));
+        }
+    }
+    Json::object(fields)
+}
+
+fn parse_occupancy(value: &Json) -> Result<Occupancy, JsonError> {
+    let limits = |key| -> Result<Option<(i64, i64)>, JsonError> {
+        match value.get(key) {
+            Ok(limits) => Ok(Some((
+                limits.get("min")?.as_i64()?,
+                limits.get("max")?.as_i64()?,
+            ))),
+            Err(_) => Ok(None),
+        }
+    };
+    Ok(Occupancy {
+        min_occupancy: value.get("min_occupancy")?.as_i64()?,
+        right: limits("right")?,
+        left: limits("left")?,
+    })
+}
+
+fn cost_json(cost: &CostModel) -> Json {
+    let fee_json = |fee: TripFee| {
+        Json::object(vec![
+            ("fixed", Json::Number(fee.fixed)),
+            ("per_person", Json::Number(fee.per_person)),
+        ])
+    };
+    Json::object(vec![
+        ("right", fee_json(cost.right)),
+        ("left", fee_json(cost.left)),
+    ])
+}
+
+fn parse_cost(value: &Json) -> Result<CostModel, JsonError> {
+    let parse_fee = |value: &Json| -> Result<TripFee, JsonError> {
+        Ok(TripFee {
+            fixed: value.get("fixed")?.as_i64()?,
+            per_person: value.get("per_person")?.as_i64()?,
+        })
+    };
+    Ok(CostModel {
+        right: parse_fee(value.get("right")?)?,
+        left: parse_fee(value.get("left")?)?,
+    })
+}
+
+pub(crate) fn parse_side(value: &Json) -> Result<bool, JsonError> {
+    match value.as_str()? {
+        "left" => Ok(true),
+        "right" => Ok(false),
+        other => Err(JsonError::new(format!("unknown side `{}`", other))),
+    }
+}
+
+// The counts, rule, rowers, occupancy and cost model of a problem; the last
+// three only when they differ from the defaults.
+pub(crate) fn problem_json(problem: &Problem) -> Json {
+    let mut fields = vec![
+        ("cannibals", Json::Number(problem.cannibals)),
+        ("missionaries", Json::Number(problem.missionaries)),
+        ("boat_capacity", Json::Number(problem.boat_capacity)),
+        ("safety", safety_json(&problem.safety)),
+    ];
+    if let Some(rowers) = &problem.rowers {
+        fields.push(("rowers", rowers_json(rowers)));
+    }
+    if problem.occupancy != Occupancy::default() {
+        fields.push(("occupancy", occupancy_json(&problem.occupancy)));
+    }
+    if problem.cost != CostModel::default() {
+        fields.push(("cost", cost_json(&problem.cost)));
+    }
+    Json::object(fields)
+}
+
+pub(crate) fn parse_problem(value: &Json) -> Result<Problem, JsonError> {
+    let problem = Problem::new(
+        value.get("cannibals")?.as_i64()?,
+        value.get("missionaries")?.as_i64()?,
+        value.get("boat_capacity")?.as_i64()?,
+    )
+    .with_safety(parse_safety(value)?);
+    let problem = match value.get("rowers") {
+        Ok(rowers) => problem.with_rowers(parse_rowers(rowers)?),
+        Err(_) => problem,
+    };
+    let problem = match value.get("occupancy") {
+        Ok(occupancy) => problem.with_occupancy(parse_occupancy(occupancy)?),
+        Err(_) => problem,
+    };
+    Ok(match value.get("cost") {
+        Ok(cost) => problem.with_cost(parse_cost(cost)?),
+        Err(_) => problem,
+    })
+}
+
+impl Solution {
+    /// Renders the problem, strategy, step count and every move together with
+    /// the bank counts once it is done.
+    pub fn to_json(&self) -> String {
+        let mut moves = Vec::new();
+        let mut state = Some(self.problem.start());
+
+        for (index, movement) in self.plan.iter().flat_map(|plan| plan.moves()).enumerate() {
+            state = state.and_then(|state| check_move(&self.problem, &state, movement).ok());
+            let mut fields = vec![
+                ("step", Json::Number(index as i64 + 1)),
+                ("direction", side(!movement.move_right)),
+                ("cannibals", Json::Number(movement.cannibals_boat)),
+                ("missionaries", Json::Number(movement.missionaries_boat)),
+            ];
+            if self.problem.rowers.is_some() {
+                fields.push(("rowers", rowers_json(&movement.rowers)));
+            }
+            fields.push((
+                "after",
+                state
+                    .as_ref()
+                    .map_or(Json::Null, |state| state_json(&self.problem, state)),
+            ));
+            moves.push(Json::object(fields));
+        }
+
+        Json::object(vec![
+            ("problem", problem_json(&self.problem)),
+            ("strategy", Json::String(self.strategy.name().to_string())),
+            ("solved", Json::Bool(self.plan.is_some())),
+            (
+                "steps",
+                self.plan
+                    .as_ref()
+                    .map_or(Json::Null, |plan| Json::Number(plan.len() as i64)),
+            ),
+            (
+                "cost",
+                self.plan
+                    .as_ref()
+                    .map_or(Json::Null, |plan| Json::Number(plan.cost())),
+            ),
+            ("moves", Json::Array(moves)),
+        ])
+        .to_pretty_string()
+    }
+
+    /// Reads a document written by [`Solution::to_json`], checking that the
+    /// recorded bank counts match a replay of the moves.
+    pub fn from_json(text: &str) -> Result<Solution, JsonError> {
+        let document = Json::parse(text)?;
+
+        let problem = parse_problem(document.get("problem")?)?;
+        let strategy_name = document.get("strategy")?.as_str()?;
+        let strategy = Strategy::from_name(strategy_name)
+            .ok_or_else(|| JsonError::new(format!("unknown strategy `{}`", strategy_name)))?;
+
+        let mut moves = Vec::new();
+        let mut state = problem.start();
+        for (index, value) in document.get("moves")?.as_array()?.iter().enumerate() {
+            let mut movement = Move::new(
+                value.get("cannibals")?.as_i64()?,
+                value.get("missionaries")?.as_i64()?,
+                !parse_side(value.get("direction")?)?,
+            );
+            if let Ok(rowers) = value.get("rowers") {
+                movement.rowers = parse_rowers(rowers)?;
+            }
+            state = check_move(&problem, &state, &movement).map_err(|rule| {
+                JsonError::new(format!("move {} is illegal: {}", index + 1, rule))
+            })?;
+            if value.get("after")? != &state_json(&problem, &state) {
+                return Err(JsonError::new(format!(
+                    "move {} records the wrong bank counts",
+                    index + 1
+                )));
+            }
+            moves.push(movement);
+        }
+
+        let plan = if document.get("solved")?.as_bool()? {
+            let steps = document.get("steps")?.as_i64()?;
+            if steps != moves.len() as i64 {
+                return Err(JsonError::new(format!(
+                    "`steps` is {} but there are {} moves",
+                    steps,
+                    moves.len()
+                )));
+            }
+            let cost = problem.cost.plan_cost(&moves);
+            let recorded = document.get("cost")?.as_i64()?;
+            if recorded != cost {
+                return Err(JsonError::new(format!(
+                    "`cost` is {} but the moves cost {}",
+                    recorded, cost
+                )));
+            }
+            Some(Plan::with_cost(moves, cost))
+        } else if moves.is_empty() {
+            None
+        } else {
+            return Err(JsonError::new("an unsolved document cannot list moves"));
+        };
+
+        Ok(Solution {
+            problem,
+            strategy,
+            plan,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::search;
+
+    #[test]
+    fn test_solution_json_round_trip() {
+        for (cannibals, missionaries, boat_capacity) in [(3, 3, 2), (4, 4, 2), (0, 0, 1), (5, 8, 3)]
+        {
+            let problem = Problem::new(cannibals, missionaries, boat_capacity);
+            for strategy in Strategy::ALL {
+                let solution = Solution {
+                    problem,
+                    strategy,
+                    plan: search(&problem, strategy),
+                };
+                assert_eq!(Solution::from_json(&solution.to_json()), Ok(solution));
+            }
+        }
+    }
+
+    #[test]
+    fn test_solution_json_safety_rule() {
+        let problem = Problem::new(4, 4, 2).with_safety(SafetyRule {
+            boat: false,
+            surplus: 1,
+            ..SafetyRule::default()
+        });
+        let solution = Solution {
+            problem,
+            strategy: Strategy::Bfs,
+            plan: search(&problem, Strategy::Bfs),
+        };
+        let text = solution.to_json();
+        assert_eq!(Solution::from_json(&text), Ok(solution));
+
+        // The rule is required rather than taken to be the classic one.
+        let document = Json::parse(&text).unwrap();
+        let Json::Object(mut fields) = document else {
+            panic!("expected an object");
+        };
+        let Json::Object(problem_fields) = &mut fields[0].1 else {
+            panic!("expected an object");
+        };
+        problem_fields.retain(|(key, _)| key != "safety");
+        let missing = Json::Object(fields).to_pretty_string();
+        assert!(Solution::from_json(&missing).is_err());
+    }
+
+    #[test]
+    fn test_solution_json_rowers() {
+        let problem = Problem::new(2, 2, 2).with_rowers(Rowers {
+            cannibals: 0,
+            missionaries: 1,
+        });
+        let solution = Solution {
+            problem,
+            strategy: Strategy::Bfs,
+            plan: search(&problem, Strategy::Bfs),
+        };
+        let text = solution.to_json();
+        assert_eq!(Solution::from_json(&text), Ok(solution));
+
+        let document = Json::parse(&text).unwrap();
+        let first = &document.get("moves").unwrap().as_array().unwrap()[0];
+        assert_eq!(first.get("rowers").unwrap(), &bank_json(0, 1));
+    }
+
+    #[test]
+    fn test_solution_json_occupancy() {
+        let problem = Problem::new(3, 3, 3).with_occupancy(Occupancy {
+            min_occupancy: 2,
+            right: Some((3, 3)),
+            left: None,
+        });
+        let solution = Solution {
+            problem,
+            strategy: Strategy::Bfs,
+            plan: search(&problem, Strategy::Bfs),
+        };
+        let text = solution.to_json();
+        assert_eq!(Solution::from_json(&text), Ok(solution));
+
+        let document = Json::parse(&text).unwrap();
+        let occupancy = document.get("problem").unwrap().get("occupancy").unwrap();
+        assert!(occupancy.get("left").is_err());
+        assert_eq!(
+            occupancy.get("right").unwrap().get("max").unwrap(),
+            &Json::Number(3)
+        );
+
+        let plain = Problem::new(3, 3, 2);
+        let document = Json::parse(
+            &Solution {
+                problem: plain,
+                strategy: Strategy::Bfs,
+                plan: None,
+            }
+            .to_json(),
+        )
+        .unwrap();
+        assert!(document.get("problem").unwrap().get("occupancy").is_err());
+    }
+
+    #[test]
+    fn test_solution_json_cost() {
+        let problem = Problem::new(3, 3, 2).with_cost(CostModel {
+            right: TripFee {
+                fixed: 2,
+                per_person: 1,
+            },
+            left: TripFee::default(),
+        });
+        let solution = Solution {
+            problem,
+            strategy: Strategy::UniformCost,
+            plan: search(&problem, Strategy::UniformCost),
+        };
+        let text = solution.to_json();
+        assert_eq!(Solution::from_json(&text), Ok(solution.clone()));
+
+        let document = Json::parse(&text).unwrap();
+        let cost = solution.plan.as_ref().unwrap().cost();
+        assert_eq!(document.get("cost").unwrap(), &Json::Number(cost));
+        assert_eq!(document.get("steps").unwrap(), &Json::Number(11));
+
+        let wrong_cost = text.replacen(
+            &format!("\"cost\": {}", cost),
+            &format!("\"cost\": {}", cost + 1),
+            1,
+        );
+        assert_ne!(wrong_cost, text);
+        assert!(Solution::from_json(&wrong_cost).is_err());
+
+        let Json::Object(mut fields) = document else {
+            panic!("expected an object");
+        };
+        fields.retain(|(key, _)| key != "cost");
+        let missing = Json::Object(fields).to_pretty_string();
+        assert!(Solution::from_json(&missing).is_err());
+    }
+
+    #[test]
+    fn test_solution_json_layout() {
+        let problem = Problem::new(1, 1, 2);
+        let solution = Solution {
+            problem,
+            strategy: Strategy::Bfs,
+            plan: search(&problem, Strategy::Bfs),
+        };
+        let document = Json::parse(&solution.to_json()).unwrap();
+
+        assert_eq!(document.get("steps").unwrap(), &Json::Number(1));
+        let first = &document.get("moves").unwrap().as_array().unwrap()[0];
+        assert_eq!(first.get("direction").unwrap().as_str().unwrap(), "right");
+        assert_eq!(
+            first.get("after").unwrap(),
+            &Json::object(vec![
+                ("left", bank_json(0, 0)),
+                ("right", bank_json(1, 1)),
+                ("boat", side(false)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_solution_from_json_rejects_inconsistent_documents() {
+        let problem = Problem::new(3, 3, 2);
+        let solution = Solution {
+            problem,
+            strategy: Strategy::AStar,
+            plan: search(&problem, Strategy::AStar),
+        };
+        let text = solution.to_json();
+
+        let wrong_steps = text.replacen("\"steps\": 11", "\"steps\": 12", 1);
+        assert!(Solution::from_json(&wrong_steps).is_err());
+
+        let wrong_strategy = text.replacen("\"astar\"", "\"random\"", 1);
+        assert!(Solution::from_json(&wrong_strategy).is_err());
+
+        let wrong_direction = text.replacen(
+            "\"right\",\n      \"cannibals\"",
+            "\"left\",\n      \"cannibals\"",
+            1,
+        );
+        assert_ne!(wrong_direction, text);
+        assert!(Solution::from_json(&wrong_direction).is_err());
+    }
+
+    #[test]
+    fn test_solution_schema_is_json() {
+        let schema = Json::parse(SOLUTION_SCHEMA).unwrap();
+        let required = schema.get("required").unwrap().as_array().unwrap();
+
+        for key in ["problem", "strategy", "solved", "steps", "moves"] {
+            assert!(required.contains(&Json::String(key.to_string())));
+        }
+    }
+}
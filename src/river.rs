@@ -0,0 +1,465 @@
+use std::cmp;
+use std::fmt;
+
+use crate::error::SolveError;
+use crate::plan::Plan;
+use crate::problem::{estimate_crossings, Problem, SafetyRule};
+use crate::search::{search, SearchProblem, Strategy};
+
+/// Missionaries and cannibals on a river with more than two places to land.
+/// Everybody starts at location 0 and has to reach the last location. Each
+/// of the `boats` only travels along `routes`, in either direction, and a
+/// step moves one boat.
+///
+/// The safety rule's `left_bank` and `right_bank` flags stand for the first
+/// and the last location. Every location in between is always checked.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiverNetwork {
+    pub cannibals: i64,
+    pub missionaries: i64,
+    pub boats: Vec<Boat>,
+    pub locations: usize,
+    pub routes: Vec<(usize, usize)>,
+    pub safety: SafetyRule,
+}
+
+/// A boat of the river, with its seats and the location it starts from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Boat {
+    pub capacity: i64,
+    pub start: usize,
+}
+
+impl RiverNetwork {
+    /// A network with a single boat starting at location 0.
+    pub fn new(
+        cannibals: i64,
+        missionaries: i64,
+        boat_capacity: i64,
+        locations: usize,
+        routes: Vec<(usize, usize)>,
+    ) -> RiverNetwork {
+        RiverNetwork {
+            cannibals,
+            missionaries,
+            boats: vec![Boat {
+                capacity: boat_capacity,
+                start: 0,
+            }],
+            locations,
+            routes,
+            safety: SafetyRule::default(),
+        }
+    }
+
+    /// Two banks with an island between them, where the boat can travel
+    /// between any two of the three.
+    pub fn island(cannibals: i64, missionaries: i64, boat_capacity: i64) -> RiverNetwork {
+        RiverNetwork::new(
+            cannibals,
+            missionaries,
+            boat_capacity,
+            3,
+            vec![(0, 1), (1, 2), (0, 2)],
+        )
+    }
+
+    /// The same network with different boats.
+    pub fn with_boats(self, boats: Vec<Boat>) -> RiverNetwork {
+        RiverNetwork { boats, ..self }
+    }
+
+    /// The same network with a different safety rule.
+    pub fn with_safety(self, safety: SafetyRule) -> RiverNetwork {
+        RiverNetwork { safety, ..self }
+    }
+
+    /// Checks that the parameters describe a puzzle that can be searched.
+    pub fn validate(&self) -> Result<(), SolveError> {
+        let smallest = self
+            .boats
+            .iter()
+            .map(|boat| boat.capacity)
+            .min()
+            .ok_or(SolveError::NoBoats)?;
+        Problem::new(self.cannibals, self.missionaries, smallest)
+            .with_safety(self.safety)
+            .validate()?;
+        if self.locations == 0 {
+            return Err(SolveError::NoLocations);
+        }
+        if self.boats.iter().any(|boat| boat.start >= self.locations) {
+            return Err(SolveError::InvalidBoat);
+        }
+        if self
+            .routes
+            .iter()
+            .any(|(from, to)| from == to || *from >= self.locations || *to >= self.locations)
+        {
+            return Err(SolveError::InvalidRoute);
+        }
+        Ok(())
+    }
+
+    /// Searches for a plan with the given strategy. `Ok(None)` means the
+    /// puzzle has no solution.
+    pub fn solve(&self, strategy: Strategy) -> Result<Option<Plan<Trip>>, SolveError> {
+        self.validate()?;
+        Ok(search(self, strategy))
+    }
+
+    // Where the boat can go from `location`, in the order the routes list
+    // them.
+    fn destinations(&self, location: usize) -> impl Iterator<Item = usize> + '_ {
+        self.routes.iter().filter_map(move |(from, to)| {
+            if *from == location {
+                Some(*to)
+            } else if *to == location {
+                Some(*from)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn is_safe(&self, state: &RiverState) -> bool {
+        let last = self.locations - 1;
+        (0..self.locations).all(|location| {
+            let checked = match location {
+                0 => self.safety.left_bank,
+                _ if location == last => self.safety.right_bank,
+                _ => true,
+            };
+            !checked
+                || self
+                    .safety
+                    .allows(state.cannibals[location], state.missionaries[location])
+        })
+    }
+}
+
+/// How many of each group are at every location, and where every boat is.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RiverState {
+    pub cannibals: Vec<i64>,
+    pub missionaries: Vec<i64>,
+    pub boats: Vec<usize>,
+}
+
+impl RiverState {
+    fn people_away_from(&self, location: usize) -> i64 {
+        self.cannibals.iter().sum::<i64>() + self.missionaries.iter().sum::<i64>()
+            - self.cannibals[location]
+            - self.missionaries[location]
+    }
+}
+
+/// A boat, by zero-based index, carrying people from one location to
+/// another.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Trip {
+    pub boat: usize,
+    pub from: usize,
+    pub to: usize,
+    pub cannibals: i64,
+    pub missionaries: i64,
+}
+
+// Written as `FROM TO C M`, the plain plan format of this puzzle, followed by
+// the boat when it is not the first one.
+impl fmt::Display for Trip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.from, self.to, self.cannibals, self.missionaries
+        )?;
+        if self.boat != 0 {
+            write!(f, " {}", self.boat)?;
+        }
+        Ok(())
+    }
+}
+
+impl SearchProblem for RiverNetwork {
+    type State = RiverState;
+    type Action = Trip;
+
+    fn initial_state(&self) -> RiverState {
+        let mut cannibals = vec![0; self.locations];
+        let mut missionaries = vec![0; self.locations];
+        // Without a location there is nowhere to start; the search then finds
+        // nothing rather than panicking on an unvalidated network.
+        if self.locations > 0 {
+            cannibals[0] = self.cannibals;
+            missionaries[0] = self.missionaries;
+        }
+        RiverState {
+            cannibals,
+            missionaries,
+            boats: self.boats.iter().map(|boat| boat.start).collect(),
+        }
+    }
+
+    fn successors(&self, state: &RiverState) -> Vec<(RiverState, Trip, i64)> {
+        let mut next_states = Vec::new();
+
+        for (boat, capacity) in self.boats.iter().map(|boat| boat.capacity).enumerate() {
+            let from = state.boats[boat];
+            for to in self.destinations(from) {
+                for cannibals in 0..=cmp::min(capacity, state.cannibals[from]) {
+                    let max_missionaries = cmp::min(capacity - cannibals, state.missionaries[from]);
+                    for missionaries in 0..=max_missionaries {
+                        if cannibals + missionaries == 0
+                            || (self.safety.boat && !self.safety.allows(cannibals, missionaries))
+                        {
+                            continue;
+                        }
+                        let mut next_state = state.clone();
+                        next_state.cannibals[from] -= cannibals;
+                        next_state.missionaries[from] -= missionaries;
+                        next_state.cannibals[to] += cannibals;
+                        next_state.missionaries[to] += missionaries;
+                        next_state.boats[boat] = to;
+                        if !self.is_safe(&next_state) {
+                            continue;
+                        }
+                        let trip = Trip {
+                            boat,
+                            from,
+                            to,
+                            cannibals,
+                            missionaries,
+                        };
+                        next_states.push((next_state, trip, 1));
+                    }
+                }
+            }
+        }
+        next_states
+    }
+
+    fn is_goal(&self, state: &RiverState) -> bool {
+        self.locations
+            .checked_sub(1)
+            .is_some_and(|last| state.people_away_from(last) == 0)
+    }
+
+    // Merging every other location into one bank turns a plan into one of the
+    // two-bank puzzle that is no longer, so its bound still holds for a single
+    // boat. A second boat can save the trips back, leaving only the bound that
+    // every trip lands at most a full boat.
+    fn heuristic(&self, state: &RiverState) -> i64 {
+        let Some(last) = self.locations.checked_sub(1) else {
+            return 0;
+        };
+        let people = state.people_away_from(last);
+        match (&self.boats[..], &state.boats[..]) {
+            ([boat], [location]) => estimate_crossings(people, *location != last, boat.capacity),
+            _ => {
+                // Boats without seats never move anybody, so any estimate
+                // holds; one seat keeps the division defined.
+                let largest = self.boats.iter().map(|boat| boat.capacity).max();
+                let largest = largest.unwrap_or(1).max(1);
+                (people + largest - 1) / largest
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::replay;
+
+    #[test]
+    fn test_two_banks_match_the_classic_puzzle() {
+        for (cannibals, missionaries, boat_capacity) in [(3, 3, 2), (4, 4, 2), (5, 5, 3), (2, 4, 2)]
+        {
+            let network =
+                RiverNetwork::new(cannibals, missionaries, boat_capacity, 2, vec![(0, 1)]);
+            let problem = Problem::new(cannibals, missionaries, boat_capacity);
+            for strategy in [Strategy::Bfs, Strategy::AStar] {
+                assert_eq!(
+                    network.solve(strategy).unwrap().map(|plan| plan.len()),
+                    problem.solve(strategy).unwrap().map(|plan| plan.len()),
+                    "{:?}",
+                    network
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_island_makes_four_pairs_solvable() {
+        assert_eq!(Problem::new(4, 4, 2).solve(Strategy::Bfs), Ok(None));
+
+        let network = RiverNetwork::island(4, 4, 2);
+        let plan = network.solve(Strategy::AStar).unwrap().unwrap();
+        assert_eq!(plan.len(), 15);
+        for strategy in Strategy::ALL {
+            let plan = network.solve(strategy).unwrap().unwrap();
+            let state = replay(&network, plan.moves()).unwrap();
+            assert!(network.is_goal(&state));
+        }
+    }
+
+    #[test]
+    fn test_island_checks_every_location() {
+        // Only the island is checked, and it cannot be left outnumbered.
+        let network = RiverNetwork::island(3, 3, 3).with_safety(SafetyRule {
+            left_bank: false,
+            right_bank: false,
+            boat: false,
+            ..SafetyRule::default()
+        });
+        let trip = |to, cannibals, missionaries| Trip {
+            boat: 0,
+            from: 0,
+            to,
+            cannibals,
+            missionaries,
+        };
+
+        assert!(replay(&network, &[trip(1, 2, 1)]).is_err());
+        assert!(replay(&network, &[trip(2, 2, 1)]).is_ok());
+        assert!(replay(&network, &[trip(1, 1, 1)]).is_ok());
+        assert_eq!(trip(1, 2, 1).to_string(), "0 1 2 1");
+    }
+
+    #[test]
+    fn test_routes_limit_the_boat() {
+        // Without the shortcut the boat cannot reach the far bank directly.
+        let chain = RiverNetwork::new(1, 1, 2, 3, vec![(0, 1), (1, 2)]);
+        let plan = chain.solve(Strategy::Bfs).unwrap().unwrap();
+        assert_eq!(plan.len(), 2);
+
+        let cut = RiverNetwork::new(1, 1, 2, 3, vec![(0, 1)]);
+        assert_eq!(cut.solve(Strategy::Bfs), Ok(None));
+    }
+
+    #[test]
+    fn test_second_boat_saves_trips_back() {
+        let boats = |capacities: &[i64], start| {
+            capacities
+                .iter()
+                .map(|capacity| Boat {
+                    capacity: *capacity,
+                    start,
+                })
+                .collect()
+        };
+        let trips =
+            |network: RiverNetwork| network.solve(Strategy::Bfs).unwrap().map(|plan| plan.len());
+        let banks = |boat_capacity| RiverNetwork::new(1, 1, boat_capacity, 2, vec![(0, 1)]);
+
+        // One seat cannot ferry two people, but two boats of one seat can.
+        assert_eq!(trips(banks(1)), None);
+        assert_eq!(trips(banks(1).with_boats(boats(&[1, 1], 0))), Some(2));
+        // A boat waiting on the far bank is no use for the first crossing.
+        assert_eq!(
+            trips(banks(1).with_boats(vec![
+                Boat {
+                    capacity: 1,
+                    start: 0,
+                },
+                Boat {
+                    capacity: 1,
+                    start: 1,
+                },
+            ])),
+            None
+        );
+
+        let classic = RiverNetwork::new(3, 3, 2, 2, vec![(0, 1)]);
+        assert_eq!(trips(classic.clone()), Some(11));
+        assert_eq!(trips(classic.with_boats(boats(&[2, 2], 0))), Some(6));
+    }
+
+    #[test]
+    fn test_every_strategy_with_two_boats() {
+        let network = RiverNetwork::island(4, 4, 2).with_boats(vec![
+            Boat {
+                capacity: 2,
+                start: 0,
+            },
+            Boat {
+                capacity: 1,
+                start: 2,
+            },
+        ]);
+        let plan = network.solve(Strategy::AStar).unwrap().unwrap();
+        assert_eq!(
+            Some(plan.len()),
+            network.solve(Strategy::Bfs).unwrap().map(|plan| plan.len())
+        );
+        for strategy in Strategy::ALL {
+            let plan = network.solve(strategy).unwrap().unwrap();
+            let state = replay(&network, plan.moves()).unwrap();
+            assert!(network.is_goal(&state));
+        }
+
+        let trip = Trip {
+            boat: 1,
+            from: 2,
+            to: 0,
+            cannibals: 0,
+            missionaries: 0,
+        };
+        assert_eq!(trip.to_string(), "2 0 0 0 1");
+    }
+
+    #[test]
+    fn test_validate_river_network() {
+        assert_eq!(RiverNetwork::island(3, 3, 2).validate(), Ok(()));
+        assert_eq!(
+            RiverNetwork::new(3, 3, 2, 0, vec![]).validate(),
+            Err(SolveError::NoLocations)
+        );
+        assert_eq!(
+            RiverNetwork::new(3, 3, 2, 2, vec![(0, 2)]).validate(),
+            Err(SolveError::InvalidRoute)
+        );
+        assert_eq!(
+            RiverNetwork::new(3, 3, 2, 2, vec![(1, 1)]).validate(),
+            Err(SolveError::InvalidRoute)
+        );
+        assert_eq!(
+            RiverNetwork::island(3, 3, 0).validate(),
+            Err(SolveError::ZeroCapacity)
+        );
+        assert_eq!(
+            RiverNetwork::island(3, 3, 2).with_boats(vec![]).validate(),
+            Err(SolveError::NoBoats)
+        );
+        assert_eq!(
+            RiverNetwork::island(3, 3, 2)
+                .with_boats(vec![Boat {
+                    capacity: 2,
+                    start: 3,
+                }])
+                .validate(),
+            Err(SolveError::InvalidBoat)
+        );
+    }
+
+    #[test]
+    fn test_search_unvalidated_network() {
+        let empty = RiverNetwork::new(3, 3, 2, 0, vec![]);
+        let seatless = RiverNetwork::island(3, 3, 0).with_boats(vec![
+            Boat {
+                capacity: 0,
+                start: 0,
+            },
+            Boat {
+                capacity: 0,
+                start: 1,
+            },
+        ]);
+        for network in [empty, seatless] {
+            for strategy in Strategy::ALL {
+                assert!(search(&network, strategy).is_none(), "{:?}", network);
+            }
+        }
+    }
+}
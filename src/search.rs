@@ -0,0 +1,584 @@
+use std::cmp;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::plan::Plan;
+
+/// Order in which discovered states are expanded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Strategy {
+    /// Depth-first search over a `Vec` stack. Fast, but plans are not shortest.
+    Dfs,
+    /// Breadth-first search over a `VecDeque`. Always returns a plan with the
+    /// fewest steps.
+    Bfs,
+    /// Greedy best-first search on the heuristic alone.
+    Greedy,
+    /// A* search on cost paid plus an admissible estimate of cost left.
+    /// Always returns a cheapest plan.
+    AStar,
+    /// Uniform-cost search on cost paid alone, ignoring the heuristic. Always
+    /// returns a cheapest plan, even when the heuristic is not admissible.
+    UniformCost,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 5] = [
+        Strategy::Dfs,
+        Strategy::Bfs,
+        Strategy::Greedy,
+        Strategy::AStar,
+        Strategy::UniformCost,
+    ];
+
+    /// Looks a strategy up by its short name (`dfs`, `bfs`, `greedy`, `astar`,
+    /// `ucs`).
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        match name {
+            "dfs" => Some(Strategy::Dfs),
+            "bfs" => Some(Strategy::Bfs),
+            "greedy" => Some(Strategy::Greedy),
+            "astar" => Some(Strategy::AStar),
+            "ucs" => Some(Strategy::UniformCost),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Dfs => "dfs",
+            Strategy::Bfs => "bfs",
+            Strategy::Greedy => "greedy",
+            Strategy::AStar => "astar",
+            Strategy::UniformCost => "ucs",
+        }
+    }
+
+    /// The queue backing this strategy, for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            Strategy::Dfs => "Vec<State>",
+            Strategy::Bfs => "VecDeque<State>",
+            Strategy::Greedy => "BinaryHeap<GreedyNode>",
+            Strategy::AStar => "BinaryHeap<SearchNode> (A*)",
+            Strategy::UniformCost => "BinaryHeap<SearchNode> (uniform cost)",
+        }
+    }
+}
+
+/// A puzzle that can be solved by searching its state space. Implementations
+/// describe the start, the moves available from each state and when the
+/// puzzle is solved; [`search`] does the rest with any [`Strategy`].
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+    type Action: Clone;
+
+    fn initial_state(&self) -> Self::State;
+
+    /// Every state reachable in one step, with the action taken and its cost.
+    fn successors(&self, state: &Self::State) -> Vec<(Self::State, Self::Action, i64)>;
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// Lower bound on the cost still needed to reach a goal. Must never
+    /// overestimate for [`Strategy::AStar`] to return a cheapest plan.
+    fn heuristic(&self, _state: &Self::State) -> i64 {
+        0
+    }
+}
+
+/// A puzzle whose steps are priced by `cost` instead of its own step costs,
+/// given the state a step starts from and the action taken. Its heuristic is
+/// zero, since nothing is known about the new costs, so [`Strategy::AStar`]
+/// and [`Strategy::UniformCost`] both return a cheapest plan.
+pub struct Costed<'a, P, F> {
+    pub problem: &'a P,
+    pub cost: F,
+}
+
+impl<P, F> SearchProblem for Costed<'_, P, F>
+where
+    P: SearchProblem,
+    F: Fn(&P::State, &P::Action) -> i64,
+{
+    type State = P::State;
+    type Action = P::Action;
+
+    fn initial_state(&self) -> P::State {
+        self.problem.initial_state()
+    }
+
+    fn successors(&self, state: &P::State) -> Vec<(P::State, P::Action, i64)> {
+        self.problem
+            .successors(state)
+            .into_iter()
+            .map(|(next_state, action, _)| {
+                let cost = (self.cost)(state, &action);
+                (next_state, action, cost)
+            })
+            .collect()
+    }
+
+    fn is_goal(&self, state: &P::State) -> bool {
+        self.problem.is_goal(state)
+    }
+}
+
+// The problem with its heuristic hidden, for uniform-cost search.
+struct Uninformed<'a, P>(&'a P);
+
+impl<P: SearchProblem> SearchProblem for Uninformed<'_, P> {
+    type State = P::State;
+    type Action = P::Action;
+
+    fn initial_state(&self) -> P::State {
+        self.0.initial_state()
+    }
+
+    fn successors(&self, state: &P::State) -> Vec<(P::State, P::Action, i64)> {
+        self.0.successors(state)
+    }
+
+    fn is_goal(&self, state: &P::State) -> bool {
+        self.0.is_goal(state)
+    }
+}
+
+// A queued state together with the cost paid to reach it (g) and the
+// estimated cost still needed (h). Ordered so that `BinaryHeap` pops the
+// node with the smallest g + h first, preferring deeper nodes on ties.
+pub(crate) struct SearchNode<S> {
+    state: S,
+    cost: i64,
+    estimate: i64,
+}
+
+impl<S> Ord for SearchNode<S> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        (self.cost + self.estimate)
+            .cmp(&(other.cost + other.estimate))
+            .reverse()
+            .then(self.cost.cmp(&other.cost))
+    }
+}
+
+impl<S> PartialOrd for SearchNode<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> PartialEq for SearchNode<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
+    }
+}
+
+impl<S> Eq for SearchNode<S> {}
+
+// A queued state ordered by its estimate alone, for greedy best-first search.
+pub(crate) struct GreedyNode<S>(SearchNode<S>);
+
+impl<S> Ord for GreedyNode<S> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.0.estimate.cmp(&other.0.estimate).reverse()
+    }
+}
+
+impl<S> PartialOrd for GreedyNode<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> PartialEq for GreedyNode<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
+    }
+}
+
+impl<S> Eq for GreedyNode<S> {}
+
+pub(crate) trait StateQueue<S> {
+    // Whether a state is queued again when a cheaper path to it turns up.
+    // Only queues ordered by cost paid need this to return cheapest plans;
+    // every other queue expands each state at most once.
+    const REOPENS: bool = false;
+
+    fn push(&mut self, node: SearchNode<S>);
+    // The next state, with the cost it was queued at when the queue keeps it.
+    fn pop(&mut self) -> Option<(S, Option<i64>)>;
+    fn is_empty(&self) -> bool;
+}
+
+impl<S> StateQueue<S> for Vec<S> {
+    fn push(&mut self, node: SearchNode<S>) {
+        self.push(node.state);
+    }
+    fn pop(&mut self) -> Option<(S, Option<i64>)> {
+        self.pop().map(|state| (state, None))
+    }
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<S> StateQueue<S> for VecDeque<S> {
+    fn push(&mut self, node: SearchNode<S>) {
+        self.push_back(node.state);
+    }
+    fn pop(&mut self) -> Option<(S, Option<i64>)> {
+        self.pop_front().map(|state| (state, None))
+    }
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<S> StateQueue<S> for BinaryHeap<GreedyNode<S>> {
+    fn push(&mut self, node: SearchNode<S>) {
+        self.push(GreedyNode(node));
+    }
+    fn pop(&mut self) -> Option<(S, Option<i64>)> {
+        self.pop().map(|node| (node.0.state, Some(node.0.cost)))
+    }
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<S> StateQueue<S> for BinaryHeap<SearchNode<S>> {
+    const REOPENS: bool = true;
+
+    fn push(&mut self, node: SearchNode<S>) {
+        self.push(node);
+    }
+    fn pop(&mut self) -> Option<(S, Option<i64>)> {
+        self.pop().map(|node| (node.state, Some(node.cost)))
+    }
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+// How the cheapest known path reaches a discovered state: the previous state
+// and action (`None` for the start) and the total cost paid.
+struct Visit<S, A> {
+    parent: Option<(S, A)>,
+    cost: i64,
+}
+
+fn reconstruct_path<S: Eq + Hash, A: Clone>(visits: &HashMap<S, Visit<S, A>>, goal: &S) -> Vec<A> {
+    let mut path = Vec::new();
+    let mut state = goal;
+    while let Some((parent, action)) = &visits[state].parent {
+        path.push(action.clone());
+        state = parent;
+    }
+    path.reverse();
+    path
+}
+
+// Searches with the queue `T`, returning the plan found and the number of
+// states whose successors were generated.
+pub(crate) fn solve<P, T>(problem: &P) -> (Option<Plan<P::Action>>, usize)
+where
+    P: SearchProblem,
+    T: Default + StateQueue<P::State>,
+{
+    let mut expanded = 0;
+    let state = problem.initial_state();
+
+    let mut visits: HashMap<P::State, Visit<P::State, P::Action>> = HashMap::new();
+    let mut queue = T::default();
+
+    visits.insert(
+        state.clone(),
+        Visit {
+            parent: None,
+            cost: 0,
+        },
+    );
+    queue.push(SearchNode {
+        estimate: problem.heuristic(&state),
+        state,
+        cost: 0,
+    });
+
+    while !queue.is_empty() {
+        let (state, queued_cost) = queue.pop().unwrap();
+
+        // A reopened state stays queued at its old, costlier path too; that
+        // entry is stale once the cheaper one has been expanded.
+        let cost_so_far = visits[&state].cost;
+        if queued_cost.is_some_and(|cost| cost > cost_so_far) {
+            continue;
+        }
+
+        if problem.is_goal(&state) {
+            let plan = Plan::with_cost(reconstruct_path(&visits, &state), cost_so_far);
+            return (Some(plan), expanded);
+        }
+
+        expanded += 1;
+
+        for (next_state, action, step_cost) in problem.successors(&state) {
+            let cost = cost_so_far + step_cost;
+            // A cost-ordered queue revisits a state when a strictly cheaper
+            // path to it is found, which keeps A* and uniform-cost search
+            // optimal; every other queue keeps the first path found.
+            if visits
+                .get(&next_state)
+                .is_some_and(|known| !T::REOPENS || known.cost <= cost)
+            {
+                continue;
+            }
+
+            visits.insert(
+                next_state.clone(),
+                Visit {
+                    parent: Some((state.clone(), action)),
+                    cost,
+                },
+            );
+
+            queue.push(SearchNode {
+                estimate: problem.heuristic(&next_state),
+                state: next_state,
+                cost,
+            });
+        }
+    }
+    (None, expanded)
+}
+
+/// Searches any [`SearchProblem`] with the given strategy. `None` means no
+/// goal state is reachable.
+pub fn search<P: SearchProblem>(problem: &P, strategy: Strategy) -> Option<Plan<P::Action>> {
+    search_counting(problem, strategy).0
+}
+
+/// Like [`search`], also returning how many states were expanded, that is
+/// taken off the queue with their successors generated.
+pub fn search_counting<P: SearchProblem>(
+    problem: &P,
+    strategy: Strategy,
+) -> (Option<Plan<P::Action>>, usize) {
+    match strategy {
+        Strategy::Dfs => solve::<P, Vec<P::State>>(problem),
+        Strategy::Bfs => solve::<P, VecDeque<P::State>>(problem),
+        Strategy::Greedy => solve::<P, BinaryHeap<GreedyNode<P::State>>>(problem),
+        Strategy::AStar => solve::<P, BinaryHeap<SearchNode<P::State>>>(problem),
+        Strategy::UniformCost => solve::<_, BinaryHeap<SearchNode<P::State>>>(&Uninformed(problem)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::StateGraph;
+    use crate::problem::{successors, CostModel, Problem, State, TripFee};
+
+    #[test]
+    fn test_solve() {
+        let problem = Problem::new(3, 3, 2);
+
+        let result_vec = solve::<_, Vec<State>>(&problem).0;
+        let result_heap = solve::<_, BinaryHeap<GreedyNode<State>>>(&problem).0;
+
+        assert!(result_vec.is_some());
+        assert!(result_heap.is_some());
+    }
+
+    // Exhaustive breadth-first search returning the minimum number of trips.
+    fn shortest_trip_count(problem: &Problem) -> Option<usize> {
+        let start = problem.start();
+        let mut distances = HashMap::from([(start.clone(), 0)]);
+        let mut frontier = vec![start];
+        let mut trips = 0;
+
+        while !frontier.is_empty() {
+            if frontier.iter().any(State::is_goal) {
+                return Some(trips);
+            }
+            trips += 1;
+            let mut next_frontier = Vec::new();
+            for state in frontier.iter() {
+                for (next_state, _) in successors(state, problem) {
+                    if !distances.contains_key(&next_state) {
+                        distances.insert(next_state.clone(), trips);
+                        next_frontier.push(next_state);
+                    }
+                }
+            }
+            frontier = next_frontier;
+        }
+        None
+    }
+
+    #[test]
+    fn test_solve_astar_is_optimal() {
+        for cannibals in 0..=6 {
+            for missionaries in 0..=6 {
+                for boat_capacity in 1..=4 {
+                    let problem = Problem::new(cannibals, missionaries, boat_capacity);
+                    let expected = shortest_trip_count(&problem);
+                    let result = solve::<_, BinaryHeap<SearchNode<State>>>(&problem).0;
+                    assert_eq!(result.map(|plan| plan.len()), expected, "{:?}", problem);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_bfs_classic() {
+        let result = solve::<_, VecDeque<State>>(&Problem::new(3, 3, 2)).0;
+
+        assert_eq!(result.map(|plan| plan.len()), Some(11));
+    }
+
+    #[test]
+    fn test_solve_bfs_is_optimal() {
+        for cannibals in 0..=6 {
+            for missionaries in 0..=6 {
+                for boat_capacity in 1..=4 {
+                    let problem = Problem::new(cannibals, missionaries, boat_capacity);
+                    let expected = shortest_trip_count(&problem);
+                    let result = solve::<_, VecDeque<State>>(&problem).0;
+                    assert_eq!(result.map(|plan| plan.len()), expected, "{:?}", problem);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_no_solution() {
+        let problem = Problem::new(4, 3, 2);
+
+        for strategy in Strategy::ALL {
+            assert!(search(&problem, strategy).is_none());
+        }
+    }
+
+    #[test]
+    fn test_solve_unsolvable_safe_start() {
+        let problem = Problem::new(4, 4, 2);
+
+        for strategy in Strategy::ALL {
+            assert!(search(&problem, strategy).is_none());
+        }
+    }
+
+    // A weighted path 0 -> 1 -> 2 -> 3 of unit steps next to an expensive
+    // shortcut 0 -> 3, to check that costs rather than steps are minimized.
+    struct Shortcut;
+
+    impl SearchProblem for Shortcut {
+        type State = u8;
+        type Action = u8;
+
+        fn initial_state(&self) -> u8 {
+            0
+        }
+
+        fn successors(&self, state: &u8) -> Vec<(u8, u8, i64)> {
+            match state {
+                0 => vec![(3, 3, 10), (1, 1, 1)],
+                1 | 2 => vec![(state + 1, state + 1, 1)],
+                _ => Vec::new(),
+            }
+        }
+
+        fn is_goal(&self, state: &u8) -> bool {
+            *state == 3
+        }
+
+        fn heuristic(&self, state: &u8) -> i64 {
+            3 - *state as i64
+        }
+    }
+
+    #[test]
+    fn test_search_generic_problem() {
+        let plan = search(&Shortcut, Strategy::AStar).unwrap();
+        assert_eq!(plan.moves(), &[1, 2, 3]);
+        assert_eq!(plan.cost(), 3);
+
+        let plan = search(&Shortcut, Strategy::Bfs).unwrap();
+        assert_eq!(plan.moves(), &[3]);
+        assert_eq!(plan.cost(), 10);
+
+        let plan = search(&Shortcut, Strategy::UniformCost).unwrap();
+        assert_eq!(plan.cost(), 3);
+    }
+
+    #[test]
+    fn test_search_expands_each_state_once() {
+        let problem = Problem::new(30, 60, 5);
+        let reachable = StateGraph::explore(&problem).unwrap().states().len();
+        for strategy in Strategy::ALL {
+            let (_, expanded) = search_counting(&problem, strategy);
+            assert!(expanded <= reachable, "{:?}: {}", strategy, expanded);
+        }
+
+        // Dearer crossings to the left reopen states the heap still holds
+        // at their first, costlier path.
+        let costed = problem.with_cost(CostModel {
+            left: TripFee {
+                fixed: 3,
+                per_person: 1,
+            },
+            ..CostModel::default()
+        });
+        for strategy in [Strategy::AStar, Strategy::UniformCost] {
+            let (_, expanded) = search_counting(&costed, strategy);
+            assert!(expanded <= reachable, "{:?}: {}", strategy, expanded);
+        }
+    }
+
+    #[test]
+    fn test_search_counting() {
+        let problem = Problem::new(3, 3, 2);
+        for strategy in Strategy::ALL {
+            let (plan, expanded) = search_counting(&problem, strategy);
+            assert_eq!(plan, search(&problem, strategy));
+            assert!((1..16).contains(&expanded), "{:?}", strategy);
+        }
+
+        // Every reachable state is expanded before giving up.
+        assert_eq!(
+            search_counting(&Problem::new(4, 4, 2), Strategy::Bfs),
+            (None, 11)
+        );
+    }
+
+    #[test]
+    fn test_search_costed_problem() {
+        // Priced by the square of the distance covered, small steps still win.
+        let problem = Costed {
+            problem: &Shortcut,
+            cost: |state: &u8, action: &u8| ((*action - *state) as i64).pow(2),
+        };
+        for strategy in [Strategy::AStar, Strategy::UniformCost] {
+            let plan = search(&problem, strategy).unwrap();
+            assert_eq!(plan.moves(), &[1, 2, 3]);
+            assert_eq!(plan.cost(), 3);
+        }
+
+        let problem = Costed {
+            problem: &Shortcut,
+            cost: |state: &u8, _: &u8| if *state == 0 { 5 } else { 10 },
+        };
+        let plan = search(&problem, Strategy::UniformCost).unwrap();
+        assert_eq!(plan.moves(), &[3]);
+        assert_eq!(plan.cost(), 5);
+    }
+
+    #[test]
+    fn test_strategy_names() {
+        for strategy in Strategy::ALL {
+            assert_eq!(Strategy::from_name(strategy.name()), Some(strategy));
+        }
+        assert_eq!(Strategy::from_name("random"), None);
+    }
+}
@@ -0,0 +1,399 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::plan::Move;
+use crate::problem::{
+    move_rowers, validate_cannibal_missionary_balance, Problem, State,
+    ValidateCannibalMissionaryBalanceProp,
+};
+use crate::search::SearchProblem;
+
+/// A rule of the puzzle that a move can break.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rule {
+    /// The move starts from the bank the boat is not on.
+    WrongDirection,
+    /// Nobody is in the boat.
+    EmptyBoat,
+    /// A negative number of people is in the boat.
+    NegativeLoad,
+    /// More people are in the boat than it can carry.
+    OverCapacity,
+    /// Fewer people are in the boat than the crossing needs.
+    TooFewPeople,
+    /// The departing bank does not have that many people.
+    NotEnoughPeople,
+    /// Nobody in the boat can row.
+    NoRower,
+    /// The boat or the departing bank does not have that many rowers.
+    NotEnoughRowers,
+    /// Cannibals outnumber missionaries on a bank or in the boat.
+    Unbalanced,
+    /// The puzzle does not offer the move from the current state.
+    IllegalMove,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::WrongDirection => write!(f, "the boat is on the other bank"),
+            Rule::EmptyBoat => write!(f, "the boat cannot cross empty"),
+            Rule::NegativeLoad => write!(f, "the boat cannot carry a negative number of people"),
+            Rule::OverCapacity => write!(f, "the boat is over capacity"),
+            Rule::TooFewPeople => write!(f, "the boat needs more people to cross"),
+            Rule::NotEnoughPeople => write!(f, "the departing bank does not have enough people"),
+            Rule::NoRower => write!(f, "nobody in the boat can row"),
+            Rule::NotEnoughRowers => write!(f, "there are not that many rowers"),
+            Rule::Unbalanced => write!(f, "cannibals outnumber missionaries"),
+            Rule::IllegalMove => write!(f, "the move is not allowed from this state"),
+        }
+    }
+}
+
+/// The first illegal move of a plan: its zero-based index and the broken rule.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PlanError {
+    pub step: usize,
+    pub rule: Rule,
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {}: {}", self.step + 1, self.rule)
+    }
+}
+
+impl Error for PlanError {}
+
+pub(crate) fn check_move(problem: &Problem, state: &State, movement: &Move) -> Result<State, Rule> {
+    if movement.move_right != state.boat_left {
+        return Err(Rule::WrongDirection);
+    }
+    let rowers = movement.rowers;
+    if movement.cannibals_boat < 0
+        || movement.missionaries_boat < 0
+        || rowers.cannibals < 0
+        || rowers.missionaries < 0
+    {
+        return Err(Rule::NegativeLoad);
+    }
+    // Counts come from user input, so no sum may be trusted not to overflow;
+    // one that does is over any capacity.
+    let Some(people) = movement
+        .cannibals_boat
+        .checked_add(movement.missionaries_boat)
+    else {
+        return Err(Rule::OverCapacity);
+    };
+    if people == 0 {
+        return Err(Rule::EmptyBoat);
+    }
+    if rowers.cannibals == 0 && rowers.missionaries == 0 {
+        return Err(Rule::NoRower);
+    }
+    let (min_people, max_people) = problem
+        .occupancy
+        .bounds(movement.move_right, problem.boat_capacity);
+    if people > max_people {
+        return Err(Rule::OverCapacity);
+    }
+    if people < min_people {
+        return Err(Rule::TooFewPeople);
+    }
+
+    let (cannibals_departing, missionaries_departing) = if state.boat_left {
+        (state.cannibals_left, state.missionaries_left)
+    } else {
+        (
+            problem.cannibals - state.cannibals_left,
+            problem.missionaries - state.missionaries_left,
+        )
+    };
+    if movement.cannibals_boat > cannibals_departing
+        || movement.missionaries_boat > missionaries_departing
+    {
+        return Err(Rule::NotEnoughPeople);
+    }
+
+    let sign = if state.boat_left { -1 } else { 1 };
+    let cannibals_left = state.cannibals_left + sign * movement.cannibals_boat;
+    let missionaries_left = state.missionaries_left + sign * movement.missionaries_boat;
+    let cannibals_right = problem.cannibals - cannibals_left;
+    let missionaries_right = problem.missionaries - missionaries_left;
+
+    // Both the rowers and the passengers of each group have to be available
+    // on the departing bank.
+    if rowers.cannibals > movement.cannibals_boat
+        || rowers.missionaries > movement.missionaries_boat
+    {
+        return Err(Rule::NotEnoughRowers);
+    }
+    let all_rowers = problem.all_rowers();
+    let rowers_left = move_rowers(state, rowers);
+    let available = |boat: i64, rowing: i64, rowing_left: i64, all: i64, left: i64, right: i64| {
+        rowing <= boat && (0..=left).contains(&rowing_left) && all - rowing_left <= right
+    };
+    if !available(
+        movement.cannibals_boat,
+        rowers.cannibals,
+        rowers_left.cannibals,
+        all_rowers.cannibals,
+        cannibals_left,
+        cannibals_right,
+    ) || !available(
+        movement.missionaries_boat,
+        rowers.missionaries,
+        rowers_left.missionaries,
+        all_rowers.missionaries,
+        missionaries_left,
+        missionaries_right,
+    ) {
+        return Err(Rule::NotEnoughRowers);
+    }
+
+    if !validate_cannibal_missionary_balance(
+        ValidateCannibalMissionaryBalanceProp {
+            cannibals_left,
+            missionaries_left,
+            cannibals_right,
+            missionaries_right,
+            cannibals_boat: movement.cannibals_boat,
+            missionaries_boat: movement.missionaries_boat,
+        },
+        &problem.safety,
+    ) {
+        return Err(Rule::Unbalanced);
+    }
+
+    Ok(State {
+        cannibals_left,
+        missionaries_left,
+        boat_left: !state.boat_left,
+        rowers_left,
+    })
+}
+
+/// Replays `moves` from the start of `problem` and returns the state they
+/// lead to, or the first move that breaks a rule. A legal plan does not have
+/// to finish the puzzle; check [`State::is_goal`] on the result for that.
+pub fn verify_plan(problem: &Problem, moves: &[Move]) -> Result<State, PlanError> {
+    let mut state = problem.start();
+    for (step, movement) in moves.iter().enumerate() {
+        state = check_move(problem, &state, movement).map_err(|rule| PlanError { step, rule })?;
+    }
+    Ok(state)
+}
+
+/// Replays `actions` on any [`SearchProblem`], accepting only the actions its
+/// `successors` offer, and returns the state they lead to. Failures are
+/// reported as [`Rule::IllegalMove`].
+pub fn replay<P>(problem: &P, actions: &[P::Action]) -> Result<P::State, PlanError>
+where
+    P: SearchProblem,
+    P::Action: PartialEq,
+{
+    let mut state = problem.initial_state();
+    for (step, action) in actions.iter().enumerate() {
+        state = problem
+            .successors(&state)
+            .into_iter()
+            .find(|(_, offered, _)| offered == action)
+            .map(|(next_state, _, _)| next_state)
+            .ok_or(PlanError {
+                step,
+                rule: Rule::IllegalMove,
+            })?;
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::{Occupancy, Rowers};
+    use crate::search::{search, Strategy};
+
+    fn movement(cannibals_boat: i64, missionaries_boat: i64, move_right: bool) -> Move {
+        Move::new(cannibals_boat, missionaries_boat, move_right)
+    }
+
+    #[test]
+    fn test_verify_plan_accepts_solutions() {
+        let problem = Problem::new(3, 3, 2);
+        for strategy in Strategy::ALL {
+            let plan = search(&problem, strategy).unwrap();
+            let state = verify_plan(&problem, plan.moves()).unwrap();
+            assert!(state.is_goal());
+        }
+    }
+
+    #[test]
+    fn test_verify_plan_partial() {
+        let problem = Problem::new(3, 3, 2);
+        let state = verify_plan(&problem, &[movement(2, 0, true)]).unwrap();
+
+        assert_eq!(state.cannibals_left, 1);
+        assert_eq!(state.missionaries_left, 3);
+        assert!(!state.boat_left);
+        assert!(!state.is_goal());
+    }
+
+    #[test]
+    fn test_verify_plan_reports_first_illegal_step() {
+        let problem = Problem::new(3, 3, 2);
+        let cases = [
+            (vec![movement(1, 0, false)], 0, Rule::WrongDirection),
+            (
+                vec![movement(2, 0, true), movement(1, 0, true)],
+                1,
+                Rule::WrongDirection,
+            ),
+            (vec![movement(0, 0, true)], 0, Rule::EmptyBoat),
+            (vec![movement(-1, 2, true)], 0, Rule::NegativeLoad),
+            (vec![movement(1, 2, true)], 0, Rule::OverCapacity),
+            (
+                vec![movement(2, 0, true), movement(0, 1, false)],
+                1,
+                Rule::NotEnoughPeople,
+            ),
+            (vec![movement(0, 2, true)], 0, Rule::Unbalanced),
+        ];
+
+        for (moves, step, rule) in cases {
+            assert_eq!(verify_plan(&problem, &moves), Err(PlanError { step, rule }));
+        }
+    }
+
+    #[test]
+    fn test_verify_plan_rowers() {
+        let problem = Problem::new(2, 2, 2).with_rowers(Rowers {
+            cannibals: 0,
+            missionaries: 1,
+        });
+        let rowed = |cannibals_boat, missionaries_boat, move_right, rowing_missionaries| Move {
+            rowers: Rowers {
+                cannibals: 0,
+                missionaries: rowing_missionaries,
+            },
+            ..movement(cannibals_boat, missionaries_boat, move_right)
+        };
+
+        let state = verify_plan(&problem, &[rowed(1, 1, true, 1)]).unwrap();
+        assert_eq!(state.rowers_left.missionaries, 0);
+
+        let cases = [
+            (vec![movement(2, 0, true)], 0, Rule::NotEnoughRowers),
+            (vec![rowed(2, 0, true, 0)], 0, Rule::NoRower),
+            (vec![rowed(0, 2, true, 2)], 0, Rule::NotEnoughRowers),
+            (
+                vec![rowed(1, 1, true, 1), rowed(1, 0, false, 0)],
+                1,
+                Rule::NoRower,
+            ),
+        ];
+        for (moves, step, rule) in cases {
+            assert_eq!(verify_plan(&problem, &moves), Err(PlanError { step, rule }));
+        }
+
+        for strategy in Strategy::ALL {
+            let plan = search(&problem, strategy).unwrap();
+            assert!(verify_plan(&problem, plan.moves()).unwrap().is_goal());
+        }
+    }
+
+    #[test]
+    fn test_verify_plan_occupancy() {
+        let problem = Problem::new(3, 3, 3).with_occupancy(Occupancy {
+            min_occupancy: 2,
+            right: None,
+            left: Some((1, 1)),
+        });
+        let cases = [
+            (vec![movement(1, 0, true)], 0, Rule::TooFewPeople),
+            (
+                vec![movement(2, 0, true), movement(2, 0, false)],
+                1,
+                Rule::OverCapacity,
+            ),
+        ];
+        for (moves, step, rule) in cases {
+            assert_eq!(verify_plan(&problem, &moves), Err(PlanError { step, rule }));
+        }
+
+        let state = verify_plan(&problem, &[movement(2, 0, true), movement(1, 0, false)]).unwrap();
+        assert_eq!(state.cannibals_left, 2);
+    }
+
+    #[test]
+    fn test_verify_plan_huge_counts() {
+        let cases = [
+            (Problem::new(3, 3, 2), movement(i64::MAX, 1, true)),
+            (
+                Problem::new(3, 3, i64::MAX),
+                movement(i64::MAX, i64::MAX, true),
+            ),
+        ];
+        for (problem, huge) in cases {
+            assert_eq!(
+                verify_plan(&problem, &[huge]),
+                Err(PlanError {
+                    step: 0,
+                    rule: Rule::OverCapacity,
+                })
+            );
+        }
+
+        let problem = Problem::new(3, 3, i64::MAX);
+        let cases = [
+            (vec![movement(i64::MAX, 0, true)], 0),
+            (vec![movement(1, 0, true), movement(i64::MAX, 0, false)], 1),
+        ];
+        for (moves, step) in cases {
+            assert_eq!(
+                verify_plan(&problem, &moves),
+                Err(PlanError {
+                    step,
+                    rule: Rule::NotEnoughPeople,
+                })
+            );
+        }
+
+        let problem = problem.with_rowers(Rowers {
+            cannibals: 1,
+            missionaries: 1,
+        });
+        let rowed = Move {
+            rowers: Rowers {
+                cannibals: i64::MAX,
+                missionaries: i64::MAX,
+            },
+            ..movement(1, 1, true)
+        };
+        assert_eq!(
+            verify_plan(&problem, &[rowed]),
+            Err(PlanError {
+                step: 0,
+                rule: Rule::NotEnoughRowers,
+            })
+        );
+    }
+
+    #[test]
+    fn test_replay_matches_verify_plan() {
+        let problem = Problem::new(3, 3, 2);
+        let plan = search(&problem, Strategy::Dfs).unwrap();
+        assert_eq!(
+            replay(&problem, plan.moves()),
+            verify_plan(&problem, plan.moves())
+        );
+
+        let moves = [movement(2, 0, true), movement(1, 0, true)];
+        assert_eq!(
+            replay(&problem, &moves),
+            Err(PlanError {
+                step: 1,
+                rule: Rule::IllegalMove,
+            })
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use astar::{Problem, Solution, Strategy, SOLUTION_SCHEMA};
+
+#[test]
+fn solutions_round_trip_through_json() {
+    for cannibals in 0..=4 {
+        for missionaries in cannibals..=5 {
+            let problem = Problem::new(cannibals, missionaries, 2);
+            for strategy in Strategy::ALL {
+                let solution = Solution {
+                    problem,
+                    strategy,
+                    plan: problem.solve(strategy).unwrap(),
+                };
+                let text = solution.to_json();
+                assert_eq!(Solution::from_json(&text), Ok(solution), "{}", text);
+            }
+        }
+    }
+}
+
+#[test]
+fn malformed_documents_are_rejected() {
+    assert!(Solution::from_json("").is_err());
+    assert!(Solution::from_json("{}").is_err());
+    assert!(Solution::from_json("[]").is_err());
+    assert!(Solution::from_json(SOLUTION_SCHEMA).is_err());
+}
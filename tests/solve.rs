@@ -0,0 +1,147 @@
+use astar::{
+    is_solvable_analytic, verify_plan, CostModel, Occupancy, Plan, Problem, Rowers, SafetyRule,
+    SolveError, Strategy, TripFee,
+};
+
+fn assert_plan_is_valid(problem: &Problem, plan: &Plan) {
+    let state = verify_plan(problem, plan.moves()).unwrap();
+    assert!(state.is_goal());
+}
+
+#[test]
+fn every_strategy_solves_the_classic_instance() {
+    let problem = Problem::new(3, 3, 2);
+
+    for strategy in Strategy::ALL {
+        let plan = problem.solve(strategy).unwrap().unwrap();
+        assert_plan_is_valid(&problem, &plan);
+    }
+}
+
+#[test]
+fn shortest_strategies_agree_with_optimal_solutions() {
+    for cannibals in 0..=5 {
+        for missionaries in 0..=5 {
+            for boat_capacity in 1..=4 {
+                let problem = Problem::new(cannibals, missionaries, boat_capacity);
+                if problem.validate() == Err(SolveError::UnsafeInitialState) {
+                    continue;
+                }
+                let optimal = problem.solve_all_optimal().unwrap();
+
+                for strategy in [Strategy::Bfs, Strategy::AStar] {
+                    let plan = problem.solve(strategy).unwrap();
+                    assert_eq!(
+                        plan.as_ref().map(Plan::len),
+                        optimal.as_ref().map(|solutions| solutions.trips()),
+                        "{:?} {:?}",
+                        problem,
+                        strategy
+                    );
+                    if let Some(plan) = plan {
+                        assert_plan_is_valid(&problem, &plan);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn analytic_solvability_agrees_with_search() {
+    for pairs in 0..=12 {
+        for boat_capacity in 1..=6 {
+            let problem = Problem::new(pairs, pairs, boat_capacity);
+            let solved = problem.solve(Strategy::Bfs).unwrap().is_some();
+            assert_eq!(
+                is_solvable_analytic(&problem),
+                Some(solved),
+                "{:?}",
+                problem
+            );
+
+            // Trip fees change the cheapest plan, not whether there is one.
+            let priced = problem.with_cost(CostModel::uniform(TripFee {
+                fixed: 2,
+                per_person: 1,
+            }));
+            assert_eq!(is_solvable_analytic(&priced), Some(solved));
+        }
+    }
+}
+
+#[test]
+fn analytic_solvability_needs_the_classic_rules() {
+    let classic = Problem::new(3, 3, 2);
+    let lenient = SafetyRule {
+        boat: false,
+        ..SafetyRule::default()
+    };
+    let rowers = Rowers {
+        cannibals: 1,
+        missionaries: 1,
+    };
+    for problem in [
+        Problem::new(2, 3, 2),
+        Problem::new(3, 3, 0),
+        classic.with_safety(lenient),
+        classic.with_rowers(rowers),
+        classic.with_occupancy(Occupancy {
+            min_occupancy: 2,
+            right: None,
+            left: None,
+        }),
+    ] {
+        assert_eq!(is_solvable_analytic(&problem), None, "{:?}", problem);
+    }
+}
+
+#[test]
+fn unsolvable_instance_returns_none() {
+    let problem = Problem::new(4, 4, 2);
+
+    for strategy in Strategy::ALL {
+        assert_eq!(problem.solve(strategy), Ok(None));
+    }
+    assert!(problem.solve_all_optimal().unwrap().is_none());
+}
+
+#[test]
+fn optimal_plans_are_enumerated_and_counted() {
+    let problem = Problem::new(3, 3, 2);
+    let solutions = problem.solve_all_optimal().unwrap().unwrap();
+    let plans: Vec<Plan> = solutions.iter().collect();
+
+    assert_eq!(solutions.count(), Some(4));
+    assert_eq!(plans.len(), 4);
+    for plan in plans.iter() {
+        assert_plan_is_valid(&problem, plan);
+    }
+    assert!(plans[0].moves()[0].move_right);
+
+    let counts = problem.count_plans(11).unwrap();
+    assert_eq!(counts.exactly(11), Some(4));
+    assert_eq!(counts.up_to(11), Some(4));
+}
+
+#[test]
+fn invalid_problems_are_rejected() {
+    assert_eq!(
+        Problem::new(-1, 3, 2).solve(Strategy::Bfs),
+        Err(SolveError::NegativeCount)
+    );
+    assert_eq!(
+        Problem::new(3, 3, 0).solve(Strategy::Bfs),
+        Err(SolveError::ZeroCapacity)
+    );
+    assert_eq!(
+        Problem::new(4, 3, 2).solve(Strategy::Bfs),
+        Err(SolveError::UnsafeInitialState)
+    );
+    assert_eq!(
+        Problem::new(i64::MAX, 0, 2).solve(Strategy::AStar),
+        Err(SolveError::Overflow)
+    );
+    assert!(Problem::new(3, 3, 0).solve_all_optimal().is_err());
+    assert!(Problem::new(3, -3, 2).count_plans(5).is_err());
+}
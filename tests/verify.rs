@@ -0,0 +1,56 @@
+use astar::{verify_plan, Move, PlanError, Problem, Rule, Strategy};
+
+fn movement(cannibals_boat: i64, missionaries_boat: i64, move_right: bool) -> Move {
+    Move::new(cannibals_boat, missionaries_boat, move_right)
+}
+
+#[test]
+fn solutions_verify_and_reach_the_goal() {
+    for (cannibals, missionaries, boat_capacity) in [(3, 3, 2), (5, 5, 3), (10, 20, 3)] {
+        let problem = Problem::new(cannibals, missionaries, boat_capacity);
+        for strategy in Strategy::ALL {
+            let plan = problem.solve(strategy).unwrap().unwrap();
+            assert!(verify_plan(&problem, plan.moves()).unwrap().is_goal());
+        }
+    }
+}
+
+#[test]
+fn tampered_plan_reports_the_broken_step() {
+    let problem = Problem::new(3, 3, 2);
+    let mut moves = problem.solve(Strategy::Bfs).unwrap().unwrap().into_moves();
+    moves[4] = movement(2, 0, moves[4].move_right);
+
+    let error = verify_plan(&problem, &moves).unwrap_err();
+    assert_eq!(error.step, 4);
+    assert_ne!(error.rule, Rule::WrongDirection);
+}
+
+#[test]
+fn dropped_move_breaks_alternation() {
+    let problem = Problem::new(3, 3, 2);
+    let mut moves = problem
+        .solve(Strategy::AStar)
+        .unwrap()
+        .unwrap()
+        .into_moves();
+    moves.remove(1);
+
+    assert_eq!(
+        verify_plan(&problem, &moves),
+        Err(PlanError {
+            step: 1,
+            rule: Rule::WrongDirection
+        })
+    );
+}
+
+#[test]
+fn empty_plan_is_legal_but_unfinished() {
+    let problem = Problem::new(3, 3, 2);
+    let state = verify_plan(&problem, &[]).unwrap();
+
+    assert!(!state.is_goal());
+    assert_eq!(state.cannibals_left, 3);
+    assert!(state.boat_left);
+}